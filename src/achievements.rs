@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_persistent::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    floor::{
+        BossKilledEvent, EnemyKilledEvent, FloorClearedEvent, FloorResource,
+        SpawnFloorEntitiesEvent,
+    },
+    manifest::achievement::{AchievementCondition, AchievementManifest},
+    player::Player,
+    statistics::Statistics,
+    stats::Revenge,
+    ui::achievements::draw_achievement_toasts,
+    GameState,
+};
+
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FloorTracker>().add_systems(
+            Update,
+            (
+                (track_enemy_kills, track_floor_start, check_achievements).chain(),
+                draw_achievement_toasts,
+            )
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+//Achievement progress that carries over between runs
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AchievementProgress {
+    pub unlocked: HashSet<String>,
+    pub enemy_kills: HashMap<String, u32>, // Enemy name -> Kill count
+}
+
+#[derive(Component)]
+pub struct AchievementToast(pub String, pub Timer);
+
+//State of the current floor used by floor related conditions
+#[derive(Resource, Default)]
+struct FloorTracker {
+    started: f32,      // Elapsed seconds when the floor started
+    damage_taken: u32, // Statistics damage taken when the floor started
+}
+
+fn track_enemy_kills(
+    mut events: EventReader<EnemyKilledEvent>,
+    mut progress: ResMut<Persistent<AchievementProgress>>,
+) {
+    if events.is_empty() {
+        return;
+    }
+
+    for event in events.read() {
+        *progress.enemy_kills.entry(event.name.clone()).or_default() += 1;
+    }
+
+    //Saved right away so kills still count when the game is closed mid run
+    let _ = progress.persist();
+}
+
+fn track_floor_start(
+    mut events: EventReader<SpawnFloorEntitiesEvent>,
+    mut tracker: ResMut<FloorTracker>,
+    statistics: Res<Persistent<Statistics>>,
+    time: Res<Time>,
+) {
+    if events.is_empty() {
+        return;
+    }
+
+    tracker.started = time.elapsed_seconds();
+    tracker.damage_taken = statistics.damage_taken;
+
+    events.clear();
+}
+
+fn check_achievements(
+    mut commands: Commands,
+    mut progress: ResMut<Persistent<AchievementProgress>>,
+    mut cleared_reader: EventReader<FloorClearedEvent>,
+    mut boss_reader: EventReader<BossKilledEvent>,
+    achievement_manifest: Res<AchievementManifest>,
+    statistics: Res<Persistent<Statistics>>,
+    tracker: Res<FloorTracker>,
+    floor: Res<FloorResource>,
+    player: Query<&Revenge, With<Player>>,
+    time: Res<Time>,
+) {
+    let flawless = !cleared_reader.is_empty() && statistics.damage_taken == tracker.damage_taken;
+    cleared_reader.clear();
+
+    let bosses_killed = boss_reader.read().map(|e| &e.0).collect::<Vec<_>>();
+    let floor_time = time.elapsed_seconds() - tracker.started;
+    let in_revenge = player.get_single().is_ok_and(|revenge| revenge.active);
+
+    let unlocked = achievement_manifest
+        .achievements
+        .values()
+        .filter(|achievement| !progress.unlocked.contains(&achievement.name))
+        .filter(|achievement| match &achievement.condition {
+            AchievementCondition::Kills { enemy, count } => {
+                progress.enemy_kills.get(enemy).copied().unwrap_or_default() >= *count
+            }
+            AchievementCondition::Statistic { stat, value } => stat.value(&statistics) >= *value,
+            AchievementCondition::ReachFloor { floor: target } => floor.floor >= *target,
+            AchievementCondition::FlawlessFloor => flawless,
+            AchievementCondition::DefeatBoss {
+                boss,
+                time_limit,
+                revenge,
+            } => {
+                bosses_killed.contains(&boss)
+                    && time_limit.is_none_or(|limit| floor_time <= limit)
+                    && (!revenge || in_revenge)
+            }
+        })
+        .map(|achievement| achievement.name.clone())
+        .collect::<Vec<_>>();
+
+    if unlocked.is_empty() {
+        return;
+    }
+
+    for name in unlocked {
+        progress.unlocked.insert(name.clone());
+        commands.spawn(AchievementToast(
+            name,
+            Timer::new(Duration::from_secs(4), TimerMode::Once),
+        ));
+    }
+
+    let _ = progress.persist();
+}
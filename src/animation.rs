@@ -24,6 +24,26 @@ pub struct Animations(pub HashMap<String, AnimationId>);
 #[derive(Component, PartialEq, Debug, Clone)] //And statefull
 pub struct DirectionalAnimations(pub HashMap<State, HashMap<Direction, AnimationId>>);
 
+impl DirectionalAnimations {
+    //Falls back to the nearest direction the sprite sheet has, as most only have four
+    pub fn get(&self, state: &State, direction: &Direction) -> Option<AnimationId> {
+        let state_animations = self.0.get(state)?;
+
+        if let Some(anim) = state_animations.get(direction) {
+            return Some(*anim);
+        }
+
+        //Checked in a fixed order so ties don't depend on the map
+        Direction::closest_of(
+            direction.vec(),
+            Direction::values()
+                .into_iter()
+                .filter(|direction| state_animations.contains_key(direction)),
+        )
+        .and_then(|closest| state_animations.get(&closest).copied())
+    }
+}
+
 pub fn animation_state(
     mut query: Query<(
         &DirectionalAnimations,
@@ -33,11 +53,9 @@ pub fn animation_state(
     )>,
 ) {
     for (animations, mut spritesheet, state, direction) in query.iter_mut() {
-        if let Some(state_animations) = animations.0.get(state) {
-            if let Some(anim) = state_animations.get(direction) {
-                if spritesheet.animation_id != *anim {
-                    spritesheet.switch(*anim);
-                }
+        if let Some(anim) = animations.get(state, direction) {
+            if spritesheet.animation_id != anim {
+                spritesheet.switch(anim);
             }
         }
     }
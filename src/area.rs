@@ -0,0 +1,391 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use bevy::{
+    prelude::*,
+    sprite::{Anchor, Mesh2dHandle},
+};
+use bevy_rapier2d::prelude::{ActiveCollisionTypes, Collider, RapierContext, Sensor};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    attack::{attack_collision_groups, AttackBundle, Damageable, Lifetime, Owner},
+    damage::{DamageEvent, Invulnerable},
+    feedback::HitFeedback,
+    state::State,
+    stats::Damage,
+    status::{ApplyStatusEvent, AttackEffects, StatusEffectData},
+    GameState, InRun,
+};
+
+pub struct AreaPlugin;
+
+impl Plugin for AreaPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                (add_cone_meshes, detonate_areas).chain(),
+                pool_ticks,
+                expand_shockwaves,
+            )
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+//Attacks that hit everything in an area instead of a single hitbox
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Reflect)]
+#[serde(tag = "shape")]
+pub enum AreaKind {
+    //Hits everything in the circle once the delay is over
+    Circle {
+        radius: f32,
+        delay: f32,
+        duration: f32,
+    },
+    //Same as the circle, but only in front of the attacker. The angle is in degrees
+    Cone {
+        radius: f32,
+        angle: f32,
+        delay: f32,
+        duration: f32,
+    },
+    //Stays on the ground and damages whatever stands in it every tick
+    Pool {
+        radius: f32,
+        duration: f32,
+        tick_rate: f32,
+        tick_multiplier: f32, // Part of the damage dealt each tick
+    },
+    //Ring that grows from the center and hits everything it passes through
+    Shockwave {
+        radius: f32,
+        speed: f32,
+        thickness: f32,
+    },
+}
+
+impl AreaKind {
+    //Seconds the attacker is busy with the attack
+    pub fn attack_time(&self) -> f32 {
+        match *self {
+            AreaKind::Circle {
+                delay, duration, ..
+            } => delay + duration,
+            AreaKind::Cone {
+                delay, duration, ..
+            } => delay + duration,
+            AreaKind::Pool { .. } => 0.,
+            AreaKind::Shockwave { radius, speed, .. } => radius / speed,
+        }
+    }
+}
+
+//Telegraphed area waiting for its delay to end before it gets a hitbox
+#[derive(Component)]
+pub struct Detonation {
+    timer: Timer,
+    collider: Collider,
+    damage: Damage,
+    duration: f32,
+    is_player_attack: bool,
+}
+
+//Drawn as a mesh, as sprites can only be rectangles
+#[derive(Component)]
+pub struct ConeShape {
+    radius: f32,
+    angle: f32, // Degrees
+    color: Color,
+}
+
+#[derive(Component)]
+pub struct Pool {
+    damage: Damage,
+    timer: Timer,
+}
+
+#[derive(Component)]
+pub struct Shockwave {
+    pub radius: f32,
+    pub max_radius: f32,
+    pub speed: f32,
+    pub thickness: f32, // Only entities this close to the edge are hit
+}
+
+const TELEGRAPH_ALPHA: f32 = 0.2;
+const ACTIVE_ALPHA: f32 = 0.5;
+
+//Fan shaped collider pointing to the right, rotated with the entity
+fn cone_collider(radius: f32, angle: f32) -> Collider {
+    const SEGMENTS: u32 = 8;
+
+    let half = angle.to_radians().min(TAU) / 2.;
+
+    let mut points = vec![Vec2::ZERO];
+    points.extend((0..=SEGMENTS).map(|i| {
+        let angle = -half + half * 2. * i as f32 / SEGMENTS as f32;
+        Vec2::from_angle(angle) * radius
+    }));
+
+    Collider::convex_hull(&points).unwrap_or(Collider::ball(radius))
+}
+
+//What an area attack does, the caller decides where it goes and who it belongs to
+pub struct AreaSpec<'a> {
+    pub area: &'a AreaKind, // Shape, telegraph and timing
+    pub damage: Damage,
+    pub effects: &'a [StatusEffectData],
+    pub feedback: HitFeedback,
+    pub is_player_attack: bool,
+}
+
+pub fn spawn_area(
+    commands: &mut Commands,
+    spec: AreaSpec,
+    position: Vec2,
+    direction: Vec2,
+    owner: Entity,
+) {
+    let AreaSpec {
+        area,
+        damage,
+        effects,
+        feedback,
+        is_player_attack,
+    } = spec;
+
+    let color = damage.kind.color();
+    let angle = f32::atan2(direction.y, direction.x);
+    let position = position.extend(5.);
+
+    //TODO: Add proper area sprites
+    let sprite = |size: Vec2, anchor: Anchor, alpha: f32| Sprite {
+        color: color.with_alpha(alpha),
+        custom_size: Some(size),
+        anchor,
+        ..default()
+    };
+
+    let mut ec = match *area {
+        AreaKind::Circle {
+            radius,
+            delay,
+            duration,
+        } => commands.spawn((
+            SpriteBundle {
+                sprite: sprite(Vec2::splat(radius * 2.), Anchor::Center, TELEGRAPH_ALPHA),
+                transform: Transform::from_translation(position),
+                ..default()
+            },
+            Detonation {
+                timer: Timer::from_seconds(delay, TimerMode::Once),
+                collider: Collider::ball(radius),
+                damage,
+                duration,
+                is_player_attack,
+            },
+        )),
+        AreaKind::Cone {
+            radius,
+            angle: cone_angle,
+            delay,
+            duration,
+        } => commands.spawn((
+            SpatialBundle::from_transform(
+                Transform::from_translation(position).with_rotation(Quat::from_rotation_z(angle)),
+            ),
+            ConeShape {
+                radius,
+                angle: cone_angle,
+                color,
+            },
+            Detonation {
+                timer: Timer::from_seconds(delay, TimerMode::Once),
+                collider: cone_collider(radius, cone_angle),
+                damage,
+                duration,
+                is_player_attack,
+            },
+        )),
+        AreaKind::Pool {
+            radius,
+            duration,
+            tick_rate,
+            tick_multiplier,
+        } => commands.spawn((
+            SpriteBundle {
+                sprite: sprite(Vec2::splat(radius * 2.), Anchor::Center, ACTIVE_ALPHA),
+                transform: Transform::from_translation(position),
+                ..default()
+            },
+            Collider::ball(radius),
+            Sensor,
+            ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_STATIC,
+            attack_collision_groups(is_player_attack),
+            Lifetime(Timer::from_seconds(duration, TimerMode::Once)),
+            Pool {
+                damage: Damage::new((damage.amount as f32 * tick_multiplier).round() as u32)
+                    .with_kind(damage.kind),
+                timer: Timer::from_seconds(tick_rate, TimerMode::Repeating),
+            },
+        )),
+        AreaKind::Shockwave {
+            radius,
+            speed,
+            thickness,
+        } => commands.spawn((
+            SpriteBundle {
+                //Scaled up with the radius, like the collider
+                sprite: sprite(Vec2::splat(2.), Anchor::Center, ACTIVE_ALPHA),
+                transform: Transform::from_translation(position),
+                ..default()
+            },
+            AttackBundle::from_collider(
+                Collider::ball(1.),
+                radius / speed,
+                damage,
+                is_player_attack,
+            ),
+            Shockwave {
+                radius: 1.,
+                max_radius: radius,
+                speed,
+                thickness,
+            },
+        )),
+    };
+
+    ec.insert((
+        AttackEffects(effects.to_vec()),
+        feedback,
+        Owner(owner),
+        StateScoped(InRun),
+    ));
+}
+
+fn add_cone_meshes(
+    query: Query<(&ConeShape, Entity), Added<ConeShape>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut commands: Commands,
+) {
+    for (cone, entity) in query.iter() {
+        //The sector points up, turned to point right like the collider
+        let mesh = Mesh::from(CircularSector::from_radians(
+            cone.radius,
+            cone.angle.to_radians().min(TAU),
+        ))
+        .rotated_by(Quat::from_rotation_z(-FRAC_PI_2));
+
+        commands.entity(entity).insert((
+            Mesh2dHandle(meshes.add(mesh)),
+            materials.add(cone.color.with_alpha(TELEGRAPH_ALPHA)),
+        ));
+    }
+}
+
+fn detonate_areas(
+    mut query: Query<(
+        &mut Detonation,
+        Option<&mut Sprite>,
+        Option<&Handle<ColorMaterial>>,
+        Entity,
+    )>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    for (mut detonation, sprite, material, entity) in query.iter_mut() {
+        detonation.timer.tick(time.delta());
+
+        if !detonation.timer.finished() {
+            continue;
+        }
+
+        if let Some(mut sprite) = sprite {
+            sprite.color.set_alpha(ACTIVE_ALPHA);
+        }
+
+        if let Some(material) = material.and_then(|material| materials.get_mut(material)) {
+            material.color.set_alpha(ACTIVE_ALPHA);
+        }
+
+        commands
+            .entity(entity)
+            .insert(AttackBundle::from_collider(
+                detonation.collider.clone(),
+                detonation.duration,
+                detonation.damage,
+                detonation.is_player_attack,
+            ))
+            .remove::<Detonation>();
+    }
+}
+
+fn pool_ticks(
+    mut pools: Query<(&mut Pool, Option<&AttackEffects>, Option<&Owner>, Entity)>,
+    damageable: Query<Option<&State>, (With<Damageable>, Without<Invulnerable>)>,
+    rapier_context: Res<RapierContext>,
+    mut damage_writer: EventWriter<DamageEvent>,
+    mut status_writer: EventWriter<ApplyStatusEvent>,
+    time: Res<Time>,
+) {
+    for (mut pool, effects, owner, entity) in pools.iter_mut() {
+        pool.timer.tick(time.delta());
+
+        if !pool.timer.just_finished() {
+            continue;
+        }
+
+        for (e1, e2, intersecting) in rapier_context.intersection_pairs_with(entity) {
+            let other = if e1 == entity { e2 } else { e1 };
+
+            //Dashing through a pool takes no damage, like any other attack
+            let Ok(state) = damageable.get(other) else {
+                continue;
+            };
+
+            if !intersecting || state.is_some_and(|state| state.equals(State::Dashing)) {
+                continue;
+            }
+
+            damage_writer.send(DamageEvent {
+                attacker: owner.map(|owner| owner.0),
+                victim: other,
+                damage: pool.damage,
+                feedback: HitFeedback::TICK,
+                knockback: None,
+            });
+
+            for effect in effects.iter().flat_map(|effects| effects.0.iter()) {
+                status_writer.send(ApplyStatusEvent {
+                    entity: other,
+                    effect: *effect,
+                });
+            }
+        }
+    }
+}
+
+fn expand_shockwaves(
+    mut query: Query<(&mut Shockwave, &mut Transform, Entity)>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    for (mut shockwave, mut transform, entity) in query.iter_mut() {
+        shockwave.radius += shockwave.speed * time.delta_seconds();
+
+        if shockwave.radius >= shockwave.max_radius {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        transform.scale = Vec3::new(shockwave.radius, shockwave.radius, 1.);
+    }
+}
+
+//Entities well inside the ring were already passed by it
+pub fn passed_by_shockwave(shockwave: &Shockwave, center: Vec2, position: Vec2) -> bool {
+    center.distance(position) < shockwave.radius - shockwave.thickness
+}
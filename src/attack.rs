@@ -1,12 +1,13 @@
+use bevy::prelude::{warn, Alpha};
 use bevy::prelude::{Event, EventReader};
 use bevy::reflect::Reflect;
 use bevy::render::texture::Image;
-use bevy::sprite::{SpriteBundle, TextureAtlas, TextureAtlasLayout};
+use bevy::sprite::{Sprite, SpriteBundle, TextureAtlas, TextureAtlasLayout};
 use bevy::time::TimerMode;
 use bevy::{
     prelude::{
         default, BuildChildren, Bundle, Commands, Component, DespawnRecursiveExt, Entity, Handle,
-        Quat, Query, Res, Transform, TransformBundle, Vec2, Vec3,
+        Quat, Query, Res, StateScoped, Transform, TransformBundle, Vec2, Vec3,
     },
     time::{Time, Timer},
 };
@@ -15,14 +16,22 @@ use bevy_rapier2d::prelude::{
 };
 use seldom_state::prelude::{Done, StateMachine};
 use seldom_state::trigger::done;
+use serde::{Deserialize, Serialize};
 
+use crate::area::{spawn_area, AreaKind, AreaSpec};
+use crate::controller::ComboChain;
+use crate::damage::DamageKind;
+use crate::feedback::HitFeedback;
 use crate::manifest::AttackData;
+use crate::projectile::{fire_shot, BurstEmitter};
+use crate::status::{AttackEffects, StatusEffectData};
 use crate::{
     collision::BodyLayers,
     movement::direction::Direction,
     movement::movement::Velocity,
     state::State,
-    stats::{Cooldown, Damage},
+    stats::{Cooldown, Damage, Revenge},
+    InRun,
 };
 
 #[derive(Component)]
@@ -43,9 +52,92 @@ pub struct Knockback {
     pub direction: Direction,
 }
 
+//Entity that spawned an attack which isn't its child (projectiles, areas)
+#[derive(Component)]
+pub struct Owner(pub Entity);
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Default, Reflect)]
+pub enum StepKind {
+    #[default]
+    Melee, // Hitbox attached to the player
+    Projectile {
+        velocity: f32,
+        lifetime: f32,
+    }, // Uses the weapon projectile texture
+    Area, // Hitbox left on the ground at the offset
+    Aoe {
+        area: AreaKind,
+    }, // Circle, cone, pool or shockwave spawned at the offset
+}
+
+//A single attack of the player's combo chain
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Reflect)]
+pub struct ComboStep {
+    #[serde(default)]
+    pub kind: StepKind,
+    pub animation: u32, // Index of the Attacking animation to play
+    pub hitbox: Vec2,
+    pub offset: f32, // Distance from the player to the hitbox center
+    #[serde(default = "default_multiplier")]
+    pub damage_multiplier: f32,
+    #[serde(default)]
+    pub damage_kind: DamageKind,
+    pub knockback: f32,
+    pub charge: f32,
+    pub attack: f32,
+    pub recover: f32,
+    pub lunge: f32, // Distance the player moves forward
+    #[serde(default)]
+    pub feedback: HitFeedback,
+    #[serde(default)]
+    pub dash_cancel: Option<f32>, // Seconds into the recovery after which a dash cancels it
+    #[serde(default)]
+    pub effects: Vec<StatusEffectData>, // Applied to everything the step hits
+}
+
+fn default_multiplier() -> f32 {
+    1.
+}
+
+//Attack charged by holding the button, the longer it's held the stronger it gets
+#[derive(Component, Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct ChargedAttack {
+    pub step: ComboStep,       // Attack released without any charge
+    pub charge_animation: u32, // Attacking animation played while charging
+    pub charge_time: f32,      // Seconds to reach full charge
+    pub release_window: f32,   // Seconds a full charge can be held before it is released
+    pub max_damage: f32,       // Multipliers at full charge
+    pub max_size: f32,
+    pub max_knockback: f32,
+}
+
+impl ChargedAttack {
+    pub fn charged_step(&self, charge: f32) -> ComboStep {
+        let charge = charge.clamp(0., 1.);
+        let scale = |max: f32| 1. + (max - 1.) * charge;
+
+        let mut step = self.step.clone();
+        step.damage_multiplier *= scale(self.max_damage);
+        step.hitbox *= scale(self.max_size);
+        step.knockback *= scale(self.max_knockback);
+        step
+    }
+}
+
+//Added while parrying, enemy attacks are negated during the AttackPhase
+#[derive(Component)]
+pub struct Parrying;
+
+#[derive(Event)]
+pub struct ParryEvent {
+    pub entity: Entity,
+    pub attacker: Option<Entity>,
+    pub position: Vec2,
+}
+
 #[derive(Component, Clone, Reflect)]
 #[component(storage = "SparseSet")]
-pub struct ChargePhase(pub Timer, pub f32); // Timer, attackphase duration for spawning attack
+pub struct ChargePhase(pub Timer, pub ComboStep, pub Vec2); // Timer, step to spawn, aim direction
 
 #[derive(Component, Clone, Reflect)]
 #[component(storage = "SparseSet")]
@@ -55,6 +147,11 @@ pub struct AttackPhase(pub Timer);
 #[component(storage = "SparseSet")]
 pub struct RecoverPhase(pub Timer);
 
+//Seconds into the RecoverPhase after which a dash can interrupt it
+#[derive(Component, Clone, Reflect)]
+#[component(storage = "SparseSet")]
+pub struct DashCancel(pub f32);
+
 pub fn attack_phase(_charge: f32, attack: f32, recover: f32) -> StateMachine {
     StateMachine::default()
         .trans::<ChargePhase, _>(
@@ -74,7 +171,7 @@ pub struct EntitiesHit(pub Vec<Entity>);
  * Generic attack bundle, missing an transform that can be added alone or with an sprite
  */
 #[derive(Bundle)]
-struct AttackBundle {
+pub struct AttackBundle {
     attack: Attack,
     entities_hit: EntitiesHit,
     collider: Collider,
@@ -86,23 +183,39 @@ struct AttackBundle {
     damage: Damage,
 }
 
+pub fn attack_collision_groups(is_player_attack: bool) -> CollisionGroups {
+    if is_player_attack {
+        CollisionGroups::new(BodyLayers::PLAYER_ATTACK, BodyLayers::ENEMY)
+    } else {
+        CollisionGroups::new(BodyLayers::ENEMY_ATTACK, BodyLayers::PLAYER)
+    }
+}
+
 impl AttackBundle {
     pub fn new(size: Vec2, duration: f32, damage: Damage, is_player_attack: bool) -> Self {
-        let collision_groups = if is_player_attack {
-            CollisionGroups::new(BodyLayers::PLAYER_ATTACK, BodyLayers::ENEMY)
-        } else {
-            CollisionGroups::new(BodyLayers::ENEMY_ATTACK, BodyLayers::PLAYER)
-        };
+        Self::from_collider(
+            Collider::cuboid(size.x / 2., size.y / 2.),
+            duration,
+            damage,
+            is_player_attack,
+        )
+    }
 
+    pub fn from_collider(
+        collider: Collider,
+        duration: f32,
+        damage: Damage,
+        is_player_attack: bool,
+    ) -> Self {
         Self {
             attack: Attack,
             entities_hit: EntitiesHit(Vec::new()),
-            collider: Collider::cuboid(size.x / 2., size.y / 2.),
+            collider,
             sensor: Sensor,
             events: ActiveEvents::COLLISION_EVENTS,
             collision_types: ActiveCollisionTypes::default()
                 | ActiveCollisionTypes::KINEMATIC_STATIC,
-            collision_groups,
+            collision_groups: attack_collision_groups(is_player_attack),
             lifetime: Lifetime(Timer::from_seconds(duration, bevy::time::TimerMode::Once)),
             damage,
         }
@@ -134,6 +247,11 @@ impl MeleeAttackBundle {
             knockback,
         }
     }
+
+    pub fn with_rotation(mut self, angle: f32) -> Self {
+        self.transform_bundle.local.rotation = Quat::from_rotation_z(angle);
+        self
+    }
 }
 
 #[derive(Bundle)]
@@ -186,6 +304,8 @@ pub struct SpawnEnemyAttack {
     pub direction: Vec2,
     pub damage: Damage,
     pub enemy_size: Vec2,
+    pub attacker: Entity,
+    pub target: Vec2,
 }
 
 pub fn attack_spawner(mut event: EventReader<SpawnEnemyAttack>, mut commands: Commands) {
@@ -195,70 +315,203 @@ pub fn attack_spawner(mut event: EventReader<SpawnEnemyAttack>, mut commands: Co
                 size,
                 duration,
                 knockback,
+                damage_kind,
+                effects,
+                feedback,
             } => {
                 let direction = Direction::from_vec2(spawn_attack.direction * -1.)
                     .expect("Bad knockback direction");
 
                 let offset = spawn_attack.direction * spawn_attack.enemy_size / 2.;
 
-                commands.spawn(MeleeAttackBundle::new(
-                    spawn_attack.position + offset.extend(0.),
-                    *size / 2.,
-                    *duration,
-                    spawn_attack.damage,
-                    Knockback {
-                        force: *knockback,
-                        direction,
-                    },
-                    false,
+                commands.spawn((
+                    MeleeAttackBundle::new(
+                        spawn_attack.position + offset.extend(0.),
+                        *size / 2.,
+                        *duration,
+                        spawn_attack.damage.with_kind(*damage_kind),
+                        Knockback {
+                            force: *knockback,
+                            direction,
+                        },
+                        false,
+                    ),
+                    AttackEffects(effects.clone()),
+                    *feedback,
+                    Owner(spawn_attack.attacker),
+                    StateScoped(InRun),
                 ));
             }
-            AttackData::Ranged {
-                texture,
-                velocity,
-                size,
-                duration,
-                atlas,
+            AttackData::Area {
+                area,
+                at_target,
+                damage_kind,
+                effects,
+                feedback,
             } => {
-                commands.spawn(ProjectileBundle::new(
-                    texture.clone(),
-                    atlas.clone(),
-                    spawn_attack.position,
-                    f32::atan2(spawn_attack.direction.y, spawn_attack.direction.x),
-                    *size / 2.,
-                    *duration,
+                let position = if *at_target {
+                    spawn_attack.target
+                } else {
+                    spawn_attack.position.truncate()
+                        + spawn_attack.direction * spawn_attack.enemy_size / 2.
+                };
+
+                spawn_area(
+                    &mut commands,
+                    AreaSpec {
+                        area,
+                        damage: spawn_attack.damage.with_kind(*damage_kind),
+                        effects,
+                        feedback: *feedback,
+                        is_player_attack: false,
+                    },
+                    position,
+                    spawn_attack.direction,
+                    spawn_attack.attacker,
+                );
+            }
+            AttackData::Ranged { pattern, .. } => {
+                fire_shot(
+                    &mut commands,
+                    &spawn_attack.data,
                     spawn_attack.damage,
-                    false,
-                    Velocity(spawn_attack.direction * *velocity, false),
-                ));
+                    spawn_attack.direction,
+                    spawn_attack.position,
+                    spawn_attack.attacker,
+                    0,
+                );
+
+                if pattern.bursts > 1 {
+                    commands.spawn((
+                        BurstEmitter {
+                            data: spawn_attack.data.clone(),
+                            damage: spawn_attack.damage,
+                            direction: spawn_attack.direction,
+                            attacker: spawn_attack.attacker,
+                            shot: 1,
+                            timer: Timer::from_seconds(pattern.interval, TimerMode::Repeating),
+                        },
+                        StateScoped(InRun),
+                    ));
+                }
             }
         }
     }
 }
 
 pub fn charge_phase_system(
-    mut query: Query<(&mut ChargePhase, &Direction, &Damage, Entity)>,
+    mut query: Query<(
+        &mut ChargePhase,
+        &Damage,
+        &Transform,
+        &ComboChain,
+        Option<&Revenge>,
+        Entity,
+    )>,
     time: Res<Time>,
     mut commands: Commands,
 ) {
-    for (mut charge_phase, direction, damage, entity) in query.iter_mut() {
+    for (mut charge_phase, damage, transform, chain, revenge, entity) in query.iter_mut() {
         if charge_phase.0.finished() {
-            commands.entity(entity).with_children(|children| {
-                let player_size = Vec2::new(32., 24.) * 0.75;
-                let offset = player_size.x * 0.75;
-
-                children.spawn(MeleeAttackBundle::new(
-                    (direction.vec() * offset).extend(10.),
-                    player_size,
-                    charge_phase.1,
-                    *damage,
-                    Knockback {
-                        force: 7.,
-                        direction: *direction,
-                    },
-                    true,
-                ));
-            });
+            let step = &charge_phase.1;
+            let multiplier = step.damage_multiplier * revenge.map_or(1., Revenge::damage_scale);
+            let damage = Damage::new((damage.amount as f32 * multiplier).round() as u32)
+                .with_kind(step.damage_kind);
+            let aim = charge_phase.2;
+            let angle = f32::atan2(aim.y, aim.x);
+            let offset = aim * step.offset;
+            let knockback = Knockback {
+                force: step.knockback,
+                direction: Direction::closest(aim),
+            };
+
+            match step.kind {
+                StepKind::Melee => {
+                    commands.entity(entity).with_children(|children| {
+                        children.spawn((
+                            MeleeAttackBundle::new(
+                                offset.extend(10.),
+                                step.hitbox,
+                                step.attack,
+                                damage,
+                                knockback,
+                                true,
+                            )
+                            .with_rotation(angle),
+                            AttackEffects(step.effects.clone()),
+                            step.feedback,
+                        ));
+                    });
+                }
+                StepKind::Projectile { velocity, lifetime } => {
+                    let Some((texture, atlas)) = &chain.projectile else {
+                        warn!("Projectile step without a projectile texture");
+                        continue;
+                    };
+
+                    commands.spawn((
+                        ProjectileBundle::new(
+                            texture.clone(),
+                            atlas.clone(),
+                            transform.translation + offset.extend(0.),
+                            angle,
+                            step.hitbox,
+                            lifetime,
+                            damage,
+                            true,
+                            Velocity(aim * velocity, false),
+                        ),
+                        Owner(entity),
+                        AttackEffects(step.effects.clone()),
+                        step.feedback,
+                        StateScoped(InRun),
+                    ));
+                }
+                StepKind::Area => {
+                    commands
+                        .spawn((
+                            MeleeAttackBundle::new(
+                                transform.translation + offset.extend(0.),
+                                step.hitbox,
+                                step.attack,
+                                damage,
+                                knockback,
+                                true,
+                            )
+                            .with_rotation(angle),
+                            Owner(entity),
+                            AttackEffects(step.effects.clone()),
+                            step.feedback,
+                            StateScoped(InRun),
+                        ))
+                        .with_children(|children| {
+                            //TODO: Add a proper area sprite
+                            children.spawn(SpriteBundle {
+                                sprite: Sprite {
+                                    color: damage.kind.color().with_alpha(0.35),
+                                    custom_size: Some(step.hitbox),
+                                    ..default()
+                                },
+                                ..default()
+                            });
+                        });
+                }
+                StepKind::Aoe { area } => {
+                    spawn_area(
+                        &mut commands,
+                        AreaSpec {
+                            area: &area,
+                            damage,
+                            effects: &step.effects,
+                            feedback: step.feedback,
+                            is_player_attack: true,
+                        },
+                        transform.translation.truncate() + offset,
+                        aim,
+                        entity,
+                    );
+                }
+            }
 
             commands.entity(entity).insert(Done::Success);
         } else {
@@ -293,7 +546,9 @@ pub fn recover_phase_system(
                 .entity(entity)
                 .insert(Done::Success)
                 .remove::<StateMachine>()
-                .remove::<RecoverPhase>();
+                .remove::<RecoverPhase>()
+                .remove::<DashCancel>()
+                .remove::<Parrying>();
         } else {
             recover_phase.0.tick(time.delta());
         }
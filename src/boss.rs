@@ -2,6 +2,8 @@ use crate::{
     animation::Animations,
     attack::Damageable,
     collision::BodyLayers,
+    damage::Resistances,
+    death::DeathEffects,
     effects::Shadow,
     enemy::state_machine,
     manifest::boss::BossData,
@@ -26,6 +28,7 @@ pub struct BossBundle {
     boss: Boss,
     stats: StatsBundle,
     damageable: Damageable,
+    resistances: Resistances,
 
     state_matchine: StateMachine,
 
@@ -37,6 +40,7 @@ pub struct BossBundle {
     animation: SpritesheetAnimation,
     animations: Animations,
     feet_offset: FeetOffset,
+    death: DeathEffects,
 
     //Physics
     collider: Collider,
@@ -56,9 +60,10 @@ impl BossBundle {
                 damage: Damage::new(data.damage),
                 speed: MovementSpeed::new(data.speed),
                 xp: XP::new(data.xp),
-                cooldown: Cooldown::new(0), //Check this
+                cooldown: Cooldown::new(data.cooldown),
             },
             damageable: Damageable,
+            resistances: Resistances(data.resistances.clone()),
 
             ysort: YSort(sorting::ENTITIES_LAYER),
             shadow: Shadow,
@@ -86,6 +91,7 @@ impl BossBundle {
             ),
             animations: data.animations.clone(),
             feet_offset: FeetOffset(data.feet_offset.unwrap_or_default()),
+            death: data.death,
 
             rigid_body: RigidBody::KinematicPositionBased,
             collider: Collider::cuboid(data.hitbox.x / 2., data.hitbox.y / 2.),
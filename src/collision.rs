@@ -1,28 +1,25 @@
 use bevy::prelude::{
-    in_state, Color, IntoSystemConfigs, Parent, Res, Text, Text2dBundle, TextStyle, Timer, Update,
-    Vec2,
+    in_state, EventWriter, GlobalTransform, Has, IntoSystemConfigs, Parent, Update,
 };
-use bevy::text::JustifyText;
-use bevy::time::TimerMode;
+use bevy::utils::HashSet;
 use bevy::{
     math::Vec3Swizzles,
     prelude::{
-        App, Camera, Commands, DespawnRecursiveExt, Entity, EventReader, Plugin, Query, Transform,
-        With, Without,
+        App, Commands, DespawnRecursiveExt, EventReader, Plugin, Query, Transform, With, Without,
     },
 };
 use bevy_rapier2d::{prelude::*, rapier::prelude::CollisionEventFlags};
-use std::time::Duration;
 
-use crate::attack::{EntitiesHit, Lifetime};
-use crate::game_states::loading::GameAssets;
-use crate::stats::Revenge;
+use crate::area::{passed_by_shockwave, Shockwave};
+use crate::attack::{AttackPhase, EntitiesHit, ParryEvent, Parrying};
+use crate::damage::{DamageEvent, HitReaction, Invulnerable};
+use crate::feedback::HitFeedback;
+use crate::state::State;
+use crate::status::{ApplyStatusEvent, AttackEffects, Stacking, StatusEffectData, StatusKind};
 use crate::{
-    attack::{Breakable, Damageable, Knockback},
-    helper::Shake,
-    movement::easing::{EaseFunction, EaseTo},
+    attack::{Breakable, Damageable, Knockback, Owner},
     player::Player,
-    stats::{Damage, Drop, Health},
+    stats::{Damage, Drop},
     GameState, XP,
 };
 
@@ -31,7 +28,8 @@ pub struct CollisionPlugin;
 impl Plugin for CollisionPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
-            .add_plugins(RapierDebugRenderPlugin::default())
+            //Toggled from the settings
+            .add_plugins(RapierDebugRenderPlugin::default().disabled())
             .add_systems(
                 Update,
                 (damageable_collision, xp_system).distributive_run_if(in_state(GameState::InGame)),
@@ -48,6 +46,7 @@ impl BodyLayers {
     pub const PLAYER_ATTACK: Group = Group::GROUP_3;
     pub const ENEMY_ATTACK: Group = Group::GROUP_4;
     pub const XP_LAYER: Group = Group::GROUP_5;
+    pub const PICKUP_LAYER: Group = Group::GROUP_6;
 }
 
 pub fn xp_system(
@@ -92,13 +91,31 @@ pub fn damageable_collision(
         Option<&Knockback>,
         Option<&mut Breakable>,
         Option<&Parent>,
+        Option<&AttackEffects>,
+        &GlobalTransform,
+        Option<&HitFeedback>,
+        Option<&Owner>,
+        Option<&Shockwave>,
     )>,
-    mut damageable_query: Query<(&mut Health, &Transform), With<Damageable>>,
-    mut player_query: Query<&mut Revenge, With<Player>>,
-    camera_query: Query<Entity, With<Camera>>,
+    damageable_query: Query<
+        (
+            &Transform,
+            Option<&HitReaction>,
+            Option<&State>,
+            Has<Invulnerable>,
+            Has<Parrying>,
+            Has<AttackPhase>,
+        ),
+        With<Damageable>,
+    >,
     mut commands: Commands,
-    game_assets: Res<GameAssets>,
+    mut damage_writer: EventWriter<DamageEvent>,
+    mut status_writer: EventWriter<ApplyStatusEvent>,
+    mut parry_writer: EventWriter<ParryEvent>,
 ) {
+    //Entities that got invulnerability this frame, as the component is only added later
+    let mut reacted = HashSet::new();
+
     events.read().for_each(|e| {
         let (e1, e2, started, flags) = match e {
             CollisionEvent::Started(e1, e2, flags) => (e1, e2, true, flags),
@@ -119,8 +136,35 @@ pub fn damageable_collision(
             (false, true) => Some((*e2, *e1)),
             _ => None,
         } {
-            let (damage, entities_hit, knockback, breakable, parent) =
-                damage_query.get_mut(damage_entity).unwrap();
+            let (
+                damage,
+                entities_hit,
+                knockback,
+                breakable,
+                parent,
+                effects,
+                attack_transform,
+                feedback,
+                owner,
+                shockwave,
+            ) = damage_query.get_mut(damage_entity).unwrap();
+
+            let (transform, hit_reaction, state, invulnerable, parrying, parry_active) =
+                damageable_query.get(damaged_entity).unwrap();
+
+            if invulnerable
+                || reacted.contains(&damaged_entity)
+                || shockwave.is_some_and(|shockwave| {
+                    passed_by_shockwave(
+                        shockwave,
+                        attack_transform.translation().xy(),
+                        transform.translation.xy(),
+                    )
+                })
+                || state.is_some_and(|state| state.equals(State::Dashing))
+            {
+                return;
+            }
 
             if let Some(mut entities_hit) = entities_hit {
                 if entities_hit.0.contains(&damaged_entity) {
@@ -130,63 +174,89 @@ pub fn damageable_collision(
                 }
             }
 
-            let (mut health, transform) = damageable_query.get_mut(damaged_entity).unwrap();
+            let attacker = parent
+                .map(|parent| parent.get())
+                .or(owner.map(|owner| owner.0));
 
-            health.damage(damage);
+            //Parries only work during their AttackPhase, the recovery is left open
+            if parrying && parry_active {
+                reacted.insert(damaged_entity);
 
-            //TODO: Handle on separate system
-            if let Some(mut breakable) = breakable {
-                if breakable.0 > 0 {
-                    breakable.0 -= 1;
+                if let Some(mut breakable) = breakable {
+                    breakable.0 = 0;
                 }
+
+                parry_writer.send(ParryEvent {
+                    entity: damaged_entity,
+                    attacker,
+                    position: attack_transform.translation().xy(),
+                });
+                return;
             }
 
-            if let Some(knockback) = knockback {
-                let new_pos =
-                    transform.translation.xy() + knockback.force * knockback.direction.vec();
-                if let Some(mut ec) = commands.get_entity(damaged_entity) {
-                    if health.current > 0 {
-                        ec.insert(EaseTo::new(new_pos, EaseFunction::EaseOutExpo, 0.5));
-                    }
+            //Attacks without knockback still push the entity away a bit when it reacts to hits
+            let push = match (knockback, hit_reaction) {
+                (Some(knockback), _) => Some((
+                    transform.translation.xy() + knockback.force * knockback.direction.vec(),
+                    0.5,
+                )),
+                (None, Some(reaction)) => {
+                    let direction = (transform.translation.xy()
+                        - attack_transform.translation().xy())
+                    .normalize_or_zero();
+
+                    Some((
+                        transform.translation.xy() + direction * reaction.knockback,
+                        0.3,
+                    ))
                 }
-            }
+                (None, None) => None,
+            };
 
-            if let Some(parent) = parent {
-                if let Ok(mut revenge) = player_query.get_mut(parent.get()) {
-                    revenge.amount += damage.amount as f32 / 10.;
+            damage_writer.send(DamageEvent {
+                attacker,
+                victim: damaged_entity,
+                damage: *damage,
+                feedback: feedback.copied().unwrap_or_default(),
+                knockback: push,
+            });
+
+            if let Some(effects) = effects {
+                for effect in effects.0.iter() {
+                    status_writer.send(ApplyStatusEvent {
+                        entity: damaged_entity,
+                        effect: *effect,
+                    });
                 }
             }
 
-            //Switch this into a shake event
-            if let Ok(camera) = camera_query.get_single() {
-                commands.entity(camera).insert(Shake {
-                    duration: 0.25,
-                    strength: (damage.amount as f32 / 25.).powi(2),
-                });
+            //TODO: Handle on separate system
+            if let Some(mut breakable) = breakable {
+                if breakable.0 > 0 {
+                    breakable.0 -= 1;
+                }
             }
 
-            //TODO: Move this into a separate system using events
-            let text_style = TextStyle {
-                font: game_assets.font.clone(),
-                font_size: 12.0,
-                color: Color::WHITE,
-            };
+            if let Some(reaction) = hit_reaction {
+                reacted.insert(damaged_entity);
+
+                status_writer.send(ApplyStatusEvent {
+                    entity: damaged_entity,
+                    effect: StatusEffectData {
+                        kind: StatusKind::Stun,
+                        duration: reaction.stun,
+                        tick_rate: None,
+                        damage: None,
+                        slow: None,
+                        stacking: Stacking::Refresh,
+                        max_stacks: None,
+                    },
+                });
 
-            //Spawn damage indicator text
-            commands.spawn((
-                Text2dBundle {
-                    text: Text::from_section(format!("-{}", damage.amount), text_style)
-                        .with_justify(JustifyText::Center),
-                    transform: Transform::from_translation(transform.translation.xy().extend(500.)),
-                    ..Default::default()
-                },
-                EaseTo::new(
-                    transform.translation.xy() + Vec2::new(0., 20.),
-                    EaseFunction::EaseOutExpo,
-                    1.,
-                ),
-                Lifetime(Timer::new(Duration::from_secs_f32(1.), TimerMode::Once)),
-            ));
+                if let Some(mut ec) = commands.get_entity(damaged_entity) {
+                    ec.try_insert(Invulnerable::new(reaction.invulnerability));
+                }
+            }
         }
     });
 }
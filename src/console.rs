@@ -0,0 +1,461 @@
+use std::f32::consts::TAU;
+
+use bevy::{math::Vec3Swizzles, prelude::*};
+use leafwing_input_manager::prelude::ActionState;
+use turborand::{rng::Rng, TurboRand};
+
+use crate::{
+    boss::Boss,
+    damage::{DamageEvent, GodMode},
+    death::Dying,
+    enemy::{state_machine::Idle, Enemy, EnemyBundle, NamePlate},
+    feedback::HitFeedback,
+    floor::{FloorResource, TriggerNextFloorEvent},
+    heal::HealEvent,
+    manifest::{enemy::EnemyManifest, floor::DomainManifest},
+    minimap::Minimap,
+    movement::movement::Follow,
+    player::{Player, PlayerActions},
+    stats::{Damage, Health, XP},
+    ui::console::draw_console,
+    GameState, InRun,
+};
+
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Console>()
+            .add_event::<ConsoleCommandEvent>()
+            .add_systems(
+                Update,
+                (toggle_console, draw_console, run_commands)
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                Update,
+                (keymap_generate, helper_camera_controller)
+                    .run_if(in_state(GameState::InGame))
+                    .run_if(console_closed),
+            )
+            .add_systems(OnExit(GameState::InGame), close_console);
+    }
+}
+
+const TOGGLE_KEY: KeyCode = KeyCode::Backquote;
+const SPAWN_DISTANCE: f32 = 48.; // Distance from the player enemies are spawned at
+const MAX_LINES: usize = 200;
+
+//Every command with its usage, also used for autocomplete
+pub const COMMANDS: [(&str, &str); 11] = [
+    ("floor", "floor <n>"),
+    ("domain", "domain <name>"),
+    ("spawn", "spawn <enemy> [count]"),
+    ("god", "god"),
+    ("give xp", "give xp <n>"),
+    ("heal", "heal"),
+    ("seed", "seed <n>"),
+    ("kill all", "kill all"),
+    ("tp portal", "tp portal"),
+    ("help", "help"),
+    ("clear", "clear"),
+];
+
+#[derive(Resource, Default)]
+pub struct Console {
+    pub open: bool,
+    pub input: String,
+    pub lines: Vec<(String, bool)>, // Text, is an error
+    pub history: Vec<String>,
+    pub history_index: Option<usize>, // Entry of the history shown in the input, None while typing
+}
+
+impl Console {
+    pub fn print(&mut self, text: impl Into<String>, error: bool) {
+        self.lines.push((text.into(), error));
+
+        if self.lines.len() > MAX_LINES {
+            self.lines.remove(0);
+        }
+    }
+
+    //Runs the input and clears it, sent to `run_commands` as an event
+    pub fn submit(&mut self) -> Option<ConsoleCommandEvent> {
+        let command = self.input.trim().to_string();
+
+        self.input.clear();
+        self.history_index = None;
+
+        if command.is_empty() {
+            return None;
+        }
+
+        self.print(format!("> {}", command), false);
+
+        if self.history.last() != Some(&command) {
+            self.history.push(command.clone());
+        }
+
+        Some(ConsoleCommandEvent(command))
+    }
+
+    pub fn history_previous(&mut self) {
+        let index = match self.history_index {
+            Some(index) => index.saturating_sub(1),
+            None if self.history.is_empty() => return,
+            None => self.history.len() - 1,
+        };
+
+        self.history_index = Some(index);
+        self.input = self.history[index].clone();
+    }
+
+    pub fn history_next(&mut self) {
+        let Some(index) = self.history_index else {
+            return;
+        };
+
+        if index + 1 < self.history.len() {
+            self.history_index = Some(index + 1);
+            self.input = self.history[index + 1].clone();
+        } else {
+            self.history_index = None;
+            self.input.clear();
+        }
+    }
+}
+
+#[derive(Event)]
+pub struct ConsoleCommandEvent(pub String);
+
+//Commands, domains or enemies that start with what was typed, as full inputs
+pub fn completions(
+    input: &str,
+    domain_manifest: &DomainManifest,
+    enemy_manifest: &EnemyManifest,
+) -> Vec<String> {
+    let matching = |command: &str, prefix: &str, names: Vec<&String>| {
+        let mut names = names
+            .into_iter()
+            .filter(|name| name.to_lowercase().starts_with(&prefix.to_lowercase()))
+            .map(|name| format!("{} {}", command, name))
+            .collect::<Vec<_>>();
+
+        names.sort();
+        names
+    };
+
+    if let Some(prefix) = input.strip_prefix("domain ") {
+        return matching(
+            "domain",
+            prefix,
+            domain_manifest
+                .domains
+                .values()
+                .map(|domain| &domain.name)
+                .collect(),
+        );
+    }
+
+    if let Some(prefix) = input.strip_prefix("spawn ") {
+        return matching(
+            "spawn",
+            prefix,
+            enemy_manifest
+                .enemies
+                .values()
+                .map(|enemy| &enemy.name)
+                .collect(),
+        );
+    }
+
+    if input.is_empty() {
+        return Vec::new();
+    }
+
+    COMMANDS
+        .iter()
+        .filter(|(command, _)| command.starts_with(input) && *command != input)
+        .map(|(command, _)| command.to_string())
+        .collect()
+}
+
+//Typing in the console shouldn't move or attack, so the player input is taken away while open
+fn toggle_console(
+    mut console: ResMut<Console>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut player_query: Query<&mut ActionState<PlayerActions>, (With<Player>, Without<Dying>)>,
+) {
+    if !keys.just_pressed(TOGGLE_KEY) {
+        return;
+    }
+
+    console.open = !console.open;
+
+    let Ok(mut action_state) = player_query.get_single_mut() else {
+        return;
+    };
+
+    if console.open {
+        action_state.disable();
+    } else {
+        action_state.enable();
+    }
+}
+
+fn console_closed(console: Res<Console>) -> bool {
+    !console.open
+}
+
+fn close_console(mut console: ResMut<Console>) {
+    console.open = false;
+    console.input.clear();
+    console.history_index = None;
+}
+
+//Clears the enemies left and generates `target` the same way the portal does
+fn go_to_floor(
+    commands: &mut Commands,
+    floor: &mut FloorResource,
+    writer: &mut EventWriter<TriggerNextFloorEvent>,
+    target: u32,
+) {
+    for entity in floor.enemies.drain(..).chain(floor.boss.take()) {
+        if let Some(entity) = commands.get_entity(entity) {
+            entity.despawn_recursive();
+        }
+    }
+
+    floor.floor = target - 1;
+    writer.send(TriggerNextFloorEvent);
+}
+
+fn run_commands(
+    mut commands: Commands,
+    mut events: EventReader<ConsoleCommandEvent>,
+    mut console: ResMut<Console>,
+    mut floor: ResMut<FloorResource>,
+    domain_manifest: Res<DomainManifest>,
+    enemy_manifest: Res<EnemyManifest>,
+    minimap: Res<Minimap>,
+    mut player_query: Query<(Entity, &mut Transform, &mut XP, &Health, Has<GodMode>), With<Player>>,
+    enemy_query: Query<
+        (Entity, &Health),
+        (
+            Or<(With<Enemy>, With<Boss>)>,
+            Without<Dying>,
+            Without<Player>,
+        ),
+    >,
+    mut floor_writer: EventWriter<TriggerNextFloorEvent>,
+    mut heal_writer: EventWriter<HealEvent>,
+    mut damage_writer: EventWriter<DamageEvent>,
+) {
+    for ConsoleCommandEvent(command) in events.read() {
+        let words = command.split_whitespace().collect::<Vec<_>>();
+        let mut player = player_query.get_single_mut().ok();
+
+        let result = match words.as_slice() {
+            ["floor", n] => match n.parse::<u32>() {
+                Ok(n) if n > 0 => {
+                    if domain_manifest
+                        .domains
+                        .values()
+                        .any(|domain| n >= domain.floors.0 && n <= domain.floors.1)
+                    {
+                        go_to_floor(&mut commands, &mut floor, &mut floor_writer, n);
+                        Ok(format!("Going to floor {}", n))
+                    } else {
+                        Err(format!("No domain has floor {}", n))
+                    }
+                }
+                _ => Err(format!("Invalid floor: {}", n)),
+            },
+            ["domain", name @ ..] if !name.is_empty() => {
+                let name = name.join(" ");
+
+                match domain_manifest
+                    .domains
+                    .values()
+                    .find(|domain| domain.name.eq_ignore_ascii_case(&name))
+                {
+                    Some(domain) => {
+                        go_to_floor(
+                            &mut commands,
+                            &mut floor,
+                            &mut floor_writer,
+                            domain.floors.0,
+                        );
+                        Ok(format!("Going to {}", domain.name))
+                    }
+                    None => Err(format!("Unknown domain: {}", name)),
+                }
+            }
+            ["spawn", name @ ..] if !name.is_empty() => {
+                //A number at the end is the amount, enemy names can have spaces
+                let (name, count) = match name
+                    .split_last()
+                    .map(|(last, rest)| (last.parse::<u32>(), rest))
+                {
+                    Some((Ok(count), rest)) if !rest.is_empty() => (rest.join(" "), count),
+                    _ => (name.join(" "), 1),
+                };
+
+                match (
+                    enemy_manifest
+                        .enemies
+                        .values()
+                        .find(|enemy| enemy.name.eq_ignore_ascii_case(&name)),
+                    player.as_ref(),
+                ) {
+                    (Some(enemy_data), Some((_, transform, ..))) => {
+                        let rand = Rng::new();
+                        let center = transform.translation.xy();
+
+                        for _ in 0..count {
+                            let pos = center + Vec2::from_angle(rand.f32() * TAU) * SPAWN_DISTANCE;
+
+                            let mut ec = commands.spawn((
+                                EnemyBundle::new(enemy_data, pos.extend(38.0)),
+                                StateScoped(InRun),
+                            ));
+                            ec.insert(Idle);
+
+                            if enemy_data.name_plate {
+                                ec.insert(NamePlate);
+                            }
+
+                            floor.enemies.push(ec.id());
+                        }
+
+                        Ok(format!("Spawned {} {}", count, enemy_data.name))
+                    }
+                    (None, _) => Err(format!("Unknown enemy: {}", name)),
+                    (_, None) => Err("No player".to_string()),
+                }
+            }
+            ["god"] => match player {
+                Some((entity, .., true)) => {
+                    commands.entity(entity).remove::<GodMode>();
+                    Ok("God mode off".to_string())
+                }
+                Some((entity, ..)) => {
+                    commands.entity(entity).insert(GodMode);
+                    Ok("God mode on".to_string())
+                }
+                None => Err("No player".to_string()),
+            },
+            ["give", "xp", n] => match (n.parse::<u32>(), player.as_mut()) {
+                (Ok(n), Some((_, _, xp, ..))) => {
+                    xp.amount += n;
+                    Ok(format!("Gave {} XP", n))
+                }
+                (Err(_), _) => Err(format!("Invalid amount: {}", n)),
+                (_, None) => Err("No player".to_string()),
+            },
+            ["heal"] => match player {
+                Some((entity, _, _, health, _)) => {
+                    heal_writer.send(HealEvent {
+                        entity,
+                        amount: health.max - health.current,
+                    });
+                    Ok("Healed".to_string())
+                }
+                None => Err("No player".to_string()),
+            },
+            ["seed", n] => match n.parse::<u64>() {
+                Ok(seed) => {
+                    floor.seed = Some(seed);
+                    Ok(format!("Seed set to {}, used from the next floor", seed))
+                }
+                Err(_) => Err(format!("Invalid seed: {}", n)),
+            },
+            ["kill", "all"] => {
+                let mut killed = 0;
+
+                //Dealt by the player like any other hit, so kills count and the portal still opens
+                for (entity, health) in enemy_query.iter() {
+                    damage_writer.send(DamageEvent {
+                        attacker: player.as_ref().map(|(player, ..)| *player),
+                        victim: entity,
+                        damage: Damage::new(health.current),
+                        feedback: HitFeedback::TICK,
+                        knockback: None,
+                    });
+                    killed += 1;
+                }
+
+                Ok(format!("Killed {} enemies", killed))
+            }
+            ["tp", "portal"] => match (
+                minimap.portal.and_then(|portal| minimap.tiles.get(&portal)),
+                player.as_mut(),
+            ) {
+                (Some(portal), Some((_, transform, ..))) => {
+                    transform.translation.x = portal.x;
+                    transform.translation.y = portal.y;
+                    Ok("Teleported to the portal".to_string())
+                }
+                (None, _) => Err("The floor has no portal".to_string()),
+                (_, None) => Err("No player".to_string()),
+            },
+            ["help"] => {
+                for (_, usage) in COMMANDS {
+                    console.print(usage, false);
+                }
+
+                continue;
+            }
+            ["clear"] => {
+                console.lines.clear();
+                continue;
+            }
+            _ => Err(format!("Unknown command: {}, try help", command)),
+        };
+
+        match result {
+            Ok(text) => console.print(text, false),
+            Err(text) => console.print(text, true),
+        }
+    }
+}
+
+//Skips to the next floor
+fn keymap_generate(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut writer: EventWriter<TriggerNextFloorEvent>,
+) {
+    if keys.just_pressed(KeyCode::ControlLeft) {
+        writer.send(TriggerNextFloorEvent);
+    }
+}
+
+//Helper camera controller
+fn helper_camera_controller(
+    mut query: Query<(&mut OrthographicProjection, &mut Transform), With<Follow>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+) {
+    if let Ok((mut projection, mut transform)) = query.get_single_mut() {
+        if keys.pressed(KeyCode::ArrowUp) {
+            transform.translation.y += 150.0 * time.delta_seconds();
+        }
+        if keys.pressed(KeyCode::ArrowLeft) {
+            transform.translation.x -= 150.0 * time.delta_seconds();
+        }
+        if keys.pressed(KeyCode::ArrowDown) {
+            transform.translation.y -= 150.0 * time.delta_seconds();
+        }
+        if keys.pressed(KeyCode::ArrowRight) {
+            transform.translation.x += 150.0 * time.delta_seconds();
+        }
+
+        if keys.pressed(KeyCode::KeyZ) {
+            projection.scale -= 1. * time.delta_seconds();
+        }
+        if keys.pressed(KeyCode::KeyX) {
+            projection.scale += 1. * time.delta_seconds();
+        }
+    }
+}
@@ -1,27 +1,86 @@
+use std::time::Duration;
+
 use bevy::{
     math::Vec2,
-    prelude::{Commands, Component, Entity, Query, Res, Transform, With},
+    prelude::{
+        Camera, Color, Commands, Component, CursorMoved, Entity, EventReader, EventWriter,
+        GlobalTransform, Handle, Has, Image, Query, Res, StateScoped, Transform, Window, With,
+        Without,
+    },
+    sprite::TextureAtlasLayout,
     time::{Time, Timer, TimerMode},
+    window::PrimaryWindow,
 };
 use bevy::{math::Vec3Swizzles, prelude::RemovedComponents};
 use leafwing_input_manager::prelude::ActionState;
+use seldom_state::prelude::StateMachine;
 
 use crate::{
-    attack::{attack_phase, ChargePhase},
+    attack::{
+        attack_phase, AttackPhase, ChargePhase, ChargedAttack, ComboStep, DashCancel, Owner,
+        ParryEvent, Parrying, ProjectileBundle, RecoverPhase,
+    },
+    damage::Invulnerable,
+    effects::floating_text,
+    game_states::loading::GameAssets,
+    helper::Shake,
     movement::{
         direction::Direction,
         easing::{EaseFunction, EaseTo},
+        movement::Velocity,
     },
     player::{Player, PlayerActions},
     state::State,
-    stats::{Cooldown, MovementSpeed},
+    stats::{Cooldown, Damage, MovementSpeed, Revenge},
+    status::{ApplyStatusEvent, Stacking, StatusEffectData, StatusEffects, StatusKind, Stunned},
+    InRun,
 };
 
+const STICK_DEADZONE: f32 = 0.2;
+
 #[derive(Component)]
 pub struct Controlled {
     pub move_to: Option<Vec2>,
 }
 
+//Attack and dash presses remembered until the player is able to act on them
+#[derive(Component)]
+pub struct InputBuffer {
+    pub window: f32, // Seconds a press is remembered
+    buffered: Option<(PlayerActions, Timer)>,
+}
+
+impl InputBuffer {
+    pub fn new(window: f32) -> Self {
+        Self {
+            window,
+            buffered: None,
+        }
+    }
+
+    pub fn pending(&self, action: PlayerActions) -> bool {
+        matches!(&self.buffered, Some((buffered, _)) if *buffered == action)
+    }
+
+    pub fn consume(&mut self) {
+        self.buffered = None;
+    }
+
+    pub fn press(&mut self, action: PlayerActions) {
+        self.buffered = Some((action, Timer::from_seconds(self.window, TimerMode::Once)));
+    }
+
+    pub fn tick(&mut self, delta: Duration) {
+        if let Some((_, timer)) = self.buffered.as_mut() {
+            timer.tick(delta);
+
+            if timer.finished() {
+                self.consume();
+            }
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct Combo {
     pub current: u32,
@@ -29,6 +88,14 @@ pub struct Combo {
     pub timer: Timer,
 }
 
+//Attacks the player goes through when attacking repeatedly, set by the equipped weapon
+#[derive(Component, Clone, Default)]
+pub struct ComboChain {
+    pub steps: Vec<ComboStep>,
+    pub window: f32, // Seconds to press attack again before the combo resets
+    pub projectile: Option<(Handle<Image>, Handle<TextureAtlasLayout>)>,
+}
+
 pub fn move_player(
     mut query: Query<
         (
@@ -38,30 +105,59 @@ pub fn move_player(
             &Transform,
             &MovementSpeed,
             &ActionState<PlayerActions>, // Entity,
+            Option<&StatusEffects>,
+            Option<&Revenge>,
         ),
-        With<Player>,
+        (With<Player>, Without<Stunned>),
     >,
     time: Res<Time>,
 ) {
-    if let Ok((mut state, mut direction, mut controlled, transform, mv_speed, action_state)) =
-        query.get_single_mut()
+    if let Ok((
+        mut state,
+        mut direction,
+        mut controlled,
+        transform,
+        mv_speed,
+        action_state,
+        effects,
+        revenge,
+    )) = query.get_single_mut()
     {
         if !(state.equals(State::Idle) || state.equals(State::Walking)) {
             return;
         }
 
         let mut dir = Vec2::ZERO;
+        let mut analog_speed = 1.;
+
+        let stick = action_state.axis_pair(&PlayerActions::Move);
 
-        for action in PlayerActions::DIRECTIONS {
-            if action_state.pressed(&action) {
-                if let Some(action_dir) = action.direction() {
-                    dir += action_dir.vec();
-                    direction.set(action_dir);
+        //The stick walks slower when it is barely tilted
+        if stick.length() > STICK_DEADZONE {
+            dir = stick;
+            analog_speed = ((stick.length() - STICK_DEADZONE) / (1. - STICK_DEADZONE)).min(1.);
+        } else {
+            for action in PlayerActions::DIRECTIONS {
+                if action_state.pressed(&action) {
+                    if let Some(action_dir) = action.direction() {
+                        dir += action_dir.vec();
+                    }
                 }
             }
         }
 
-        let dir = dir.normalize_or_zero() * mv_speed.speed as f32 * time.delta_seconds();
+        //Opposite keys cancel out and keep the last direction
+        if let Some(new_direction) = Direction::from_vec2(dir) {
+            direction.set(new_direction);
+        }
+
+        let speed_multiplier = effects.map_or(1., |effects| effects.speed_multiplier())
+            * revenge.map_or(1., Revenge::speed_scale);
+        let dir = dir.normalize_or_zero()
+            * mv_speed.speed as f32
+            * speed_multiplier
+            * analog_speed
+            * time.delta_seconds();
 
         if dir.x == 0. && dir.y == 0. {
             state.set(State::Idle);
@@ -74,6 +170,22 @@ pub fn move_player(
     }
 }
 
+//The latest press wins, so a dash pressed after an attack replaces it
+pub fn buffer_inputs(
+    mut query: Query<(&mut InputBuffer, &ActionState<PlayerActions>)>,
+    time: Res<Time>,
+) {
+    for (mut buffer, action_state) in query.iter_mut() {
+        buffer.tick(time.delta());
+
+        for action in [PlayerActions::Attack, PlayerActions::Dash] {
+            if action_state.just_pressed(&action) {
+                buffer.press(action);
+            }
+        }
+    }
+}
+
 pub fn dash_ability(
     mut query: Query<
         (
@@ -82,25 +194,49 @@ pub fn dash_ability(
             &Direction,
             &ActionState<PlayerActions>,
             &mut Cooldown,
+            &mut InputBuffer,
+            Option<&RecoverPhase>,
+            Option<&DashCancel>,
             Entity,
         ),
-        With<Player>,
+        (With<Player>, Without<Stunned>),
     >,
     mut commands: Commands,
 ) {
-    if let Ok((mut state, transform, direction, action_state, mut cooldown, entity)) =
-        query.get_single_mut()
+    if let Ok((
+        mut state,
+        transform,
+        direction,
+        action_state,
+        mut cooldown,
+        mut buffer,
+        recover,
+        dash_cancel,
+        entity,
+    )) = query.get_single_mut()
     {
         let mut dir = Vec2::ZERO;
 
-        if matches!(*state, State::Attacking(_)) || state.equals(State::Dashing) {
+        let cancel = matches!(*state, State::Attacking(_))
+            && recover
+                .zip(dash_cancel)
+                .is_some_and(|(recover, cancel)| recover.0.elapsed_secs() >= cancel.0);
+
+        if (matches!(*state, State::Attacking(_)) && !cancel) || state.equals(State::Dashing) {
             return;
         }
 
-        for action in PlayerActions::DIRECTIONS {
-            if action_state.pressed(&action) {
-                if let Some(action_dir) = action.direction() {
-                    dir += action_dir.vec();
+        //Same input as walking, the stick first and then the movement buttons
+        let stick = action_state.axis_pair(&PlayerActions::Move);
+
+        if stick.length() > STICK_DEADZONE {
+            dir = stick;
+        } else {
+            for action in PlayerActions::DIRECTIONS {
+                if action_state.pressed(&action) {
+                    if let Some(action_dir) = action.direction() {
+                        dir += action_dir.vec();
+                    }
                 }
             }
         }
@@ -109,13 +245,19 @@ pub fn dash_ability(
             dir = direction.vec();
         }
 
-        if action_state.just_pressed(&PlayerActions::Dash) && cooldown.is_ready() {
+        //The attack that is being canceled just reset the shared cooldown
+        if buffer.pending(PlayerActions::Dash) && (cooldown.is_ready() || cancel) {
+            buffer.consume();
             state.set(State::Dashing);
             cooldown.reset();
 
             //TODO: Add dash stats
             let new_pos = transform.translation.xy() + (dir.normalize() * 45.);
             if let Some(mut ec) = commands.get_entity(entity) {
+                if cancel {
+                    ec.remove::<(StateMachine, RecoverPhase, DashCancel)>();
+                }
+
                 ec.insert(EaseTo::new(new_pos, EaseFunction::EaseOutQuad, 0.35));
             }
         }
@@ -135,51 +277,237 @@ pub fn finish_dash(
     }
 }
 
+//Where the player attacks, from the mouse or the right stick
+#[derive(Component, Default)]
+pub struct Aiming {
+    pub direction: Option<Vec2>, // None when aiming with the movement keys
+    using_mouse: bool,
+}
+
+impl Aiming {
+    pub fn vec(&self, facing: &Direction) -> Vec2 {
+        self.direction.unwrap_or(facing.vec())
+    }
+}
+
+//Projectile the player fires with the secondary attack
+#[derive(Component)]
+pub struct RangedAttack {
+    pub damage_multiplier: f32,
+    pub velocity: f32,
+    pub lifetime: f32,
+    pub size: Vec2,
+    pub texture: Handle<Image>,
+    pub atlas: Handle<TextureAtlasLayout>,
+    pub cooldown: Timer,
+}
+
+pub fn update_aim(
+    mut query: Query<(&mut Aiming, &Transform, &ActionState<PlayerActions>), With<Player>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut cursor_events: EventReader<CursorMoved>,
+) {
+    let Ok((mut aim, transform, action_state)) = query.get_single_mut() else {
+        return;
+    };
+
+    if cursor_events.read().last().is_some() {
+        aim.using_mouse = true;
+    }
+
+    let stick = action_state.axis_pair(&PlayerActions::Aim);
+
+    if stick.length() > STICK_DEADZONE {
+        aim.using_mouse = false;
+        aim.direction = Some(stick.normalize());
+        return;
+    }
+
+    if !aim.using_mouse {
+        //Keep the last stick direction until the player moves with the keys
+        if PlayerActions::DIRECTIONS
+            .iter()
+            .any(|action| action_state.pressed(action))
+        {
+            aim.direction = None;
+        }
+        return;
+    }
+
+    let (Ok((camera, camera_transform)), Ok(window)) =
+        (camera_query.get_single(), window_query.get_single())
+    else {
+        return;
+    };
+
+    if let Some(cursor) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor))
+    {
+        aim.direction = (cursor - transform.translation.xy()).try_normalize();
+    }
+}
+
+pub fn secondary_attack_ability(
+    mut query: Query<
+        (
+            &mut RangedAttack,
+            &State,
+            &ActionState<PlayerActions>,
+            &Transform,
+            &Direction,
+            &Damage,
+            Option<&Aiming>,
+            Option<&Revenge>,
+            Entity,
+        ),
+        Without<Stunned>,
+    >,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    let Ok((
+        mut secondary,
+        state,
+        action_state,
+        transform,
+        direction,
+        damage,
+        aim,
+        revenge,
+        entity,
+    )) = query.get_single_mut()
+    else {
+        return;
+    };
+
+    secondary.cooldown.tick(time.delta());
+
+    if state.equals(State::Dashing) || matches!(*state, State::Attacking(_)) {
+        return;
+    }
+
+    if !action_state.just_pressed(&PlayerActions::SecondaryAttack) || !secondary.cooldown.finished()
+    {
+        return;
+    }
+
+    secondary.cooldown.reset();
+
+    let aim = aim.map_or(direction.vec(), |aim| aim.vec(direction));
+    let multiplier = secondary.damage_multiplier * revenge.map_or(1., Revenge::damage_scale);
+    let damage =
+        Damage::new((damage.amount as f32 * multiplier).round() as u32).with_kind(damage.kind);
+
+    commands.spawn((
+        ProjectileBundle::new(
+            secondary.texture.clone(),
+            secondary.atlas.clone(),
+            transform.translation + (aim * 10.).extend(0.),
+            f32::atan2(aim.y, aim.x),
+            secondary.size,
+            secondary.lifetime,
+            damage,
+            true,
+            Velocity(aim * secondary.velocity, false),
+        ),
+        Owner(entity),
+        StateScoped(InRun),
+    ));
+}
+
 pub fn attack_ability(
-    mut query: Query<(
-        &mut State,
-        &ActionState<PlayerActions>,
-        &Transform,
-        &Direction,
-        &mut Cooldown,
-        Option<&mut Combo>,
-        Entity,
-    )>,
+    mut query: Query<
+        (
+            &mut State,
+            &Transform,
+            &mut Direction,
+            &mut Cooldown,
+            &mut InputBuffer,
+            &ComboChain,
+            Option<&mut Combo>,
+            Option<&Aiming>,
+            Option<&Revenge>,
+            Entity,
+        ),
+        (With<ActionState<PlayerActions>>, Without<Stunned>),
+    >,
     mut commands: Commands,
 ) {
-    if let Ok((mut state, action_state, transform, direction, mut cooldown, combo, entity)) =
-        query.get_single_mut()
+    if let Ok((
+        mut state,
+        transform,
+        mut direction,
+        mut cooldown,
+        mut buffer,
+        chain,
+        combo,
+        aim,
+        revenge,
+        entity,
+    )) = query.get_single_mut()
     {
         if state.equals(State::Dashing) || matches!(*state, State::Attacking(_)) {
             return;
         }
 
-        if action_state.just_pressed(&PlayerActions::Attack) && cooldown.is_ready() {
-            if let Some(mut combo) = combo {
+        if chain.steps.is_empty() {
+            return;
+        }
+
+        if buffer.pending(PlayerActions::Attack) && cooldown.is_ready() {
+            buffer.consume();
+
+            let extra_step = revenge.and_then(Revenge::extra_step);
+            let max = chain.steps.len() as u32 - 1 + extra_step.is_some() as u32;
+
+            let current = if let Some(mut combo) = combo {
                 combo.current += 1;
+                combo.max = max;
                 combo.timer.reset();
                 if combo.current > combo.max {
                     combo.current = 0;
                 }
-                state.set(State::Attacking(combo.current));
+                combo.current
             } else {
-                state.set(State::Attacking(0));
                 commands.entity(entity).insert(Combo {
                     current: 0,
-                    max: 2,
-                    timer: Timer::from_seconds(1., bevy::time::TimerMode::Once),
+                    max,
+                    timer: Timer::from_seconds(chain.window, bevy::time::TimerMode::Once),
                 });
-            }
+                0
+            };
+
+            let step = chain
+                .steps
+                .get(current as usize)
+                .or(extra_step)
+                .unwrap_or(&chain.steps[0])
+                .clone();
+            state.set(State::Attacking(step.animation));
 
             cooldown.reset();
 
-            //TODO: Add attack dash stats
-            let new_pos = transform.translation.xy() + (direction.vec().normalize() * 5.);
+            //Attacks go where the player aims, the animation uses the closest direction
+            let aim = aim.map_or(direction.vec(), |aim| aim.vec(&direction));
+            direction.set(Direction::closest(aim));
+
+            let new_pos = transform.translation.xy() + (aim.normalize() * step.lunge);
 
             if let Some(mut ec) = commands.get_entity(entity) {
-                ec.insert(attack_phase(0.05, 0.2, 0.075))
-                    .insert(ChargePhase(Timer::from_seconds(0.05, TimerMode::Once), 0.2))
-                    .insert(EaseTo::new(new_pos, EaseFunction::EaseOutQuad, 0.5));
+                match step.dash_cancel {
+                    Some(window) => ec.insert(DashCancel(window)),
+                    None => ec.remove::<DashCancel>(),
+                };
+
+                ec.insert(attack_phase(step.charge, step.attack, step.recover))
+                    .insert(EaseTo::new(new_pos, EaseFunction::EaseOutQuad, 0.5))
+                    .insert(ChargePhase(
+                        Timer::from_seconds(step.charge, TimerMode::Once),
+                        step,
+                        aim,
+                    ));
             } else {
                 println!("Failed to get entity");
                 state.set(State::Idle);
@@ -201,3 +529,230 @@ pub fn combo_system(
         }
     }
 }
+
+//Seconds the heavy attack has been held for
+#[derive(Component)]
+pub struct Charging(pub f32);
+
+pub fn heavy_attack_ability(
+    mut query: Query<
+        (
+            &mut State,
+            &ActionState<PlayerActions>,
+            &Transform,
+            &mut Direction,
+            &ChargedAttack,
+            Option<&mut Charging>,
+            Option<&Aiming>,
+            Has<Stunned>,
+            Entity,
+        ),
+        With<Player>,
+    >,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    let Ok((
+        mut state,
+        action_state,
+        transform,
+        mut direction,
+        heavy,
+        charging,
+        aim,
+        stunned,
+        entity,
+    )) = query.get_single_mut()
+    else {
+        return;
+    };
+
+    let Some(mut charging) = charging else {
+        if !stunned
+            && (state.equals(State::Idle) || state.equals(State::Walking))
+            && action_state.just_pressed(&PlayerActions::HeavyAttack)
+        {
+            state.set(State::Attacking(heavy.charge_animation));
+            commands.entity(entity).insert(Charging(0.));
+        }
+        return;
+    };
+
+    //Getting stunned or swapping weapons cancels the charge
+    if stunned || !state.equals(State::Attacking(heavy.charge_animation)) {
+        if stunned {
+            state.set(State::Idle);
+        }
+        commands.entity(entity).remove::<Charging>();
+        return;
+    }
+
+    charging.0 += time.delta_seconds();
+
+    let aim = aim.map_or(direction.vec(), |aim| aim.vec(&direction));
+    direction.set(Direction::closest(aim));
+
+    //Holding a full charge past the release window lets it go on its own
+    if !action_state.released(&PlayerActions::HeavyAttack)
+        && charging.0 < heavy.charge_time + heavy.release_window
+    {
+        return;
+    }
+
+    let step = heavy.charged_step(charging.0 / heavy.charge_time);
+    state.set(State::Attacking(step.animation));
+
+    let new_pos = transform.translation.xy() + (aim.normalize() * step.lunge);
+
+    commands
+        .entity(entity)
+        .remove::<Charging>()
+        .insert(attack_phase(step.charge, step.attack, step.recover))
+        .insert(EaseTo::new(new_pos, EaseFunction::EaseOutQuad, 0.5))
+        .insert(ChargePhase(
+            Timer::from_seconds(step.charge, TimerMode::Once),
+            step,
+            aim,
+        ));
+}
+
+#[derive(Component)]
+pub struct ParryAbility {
+    pub animation: u32,
+    pub window: f32,
+    pub recover: f32,
+    pub stun: f32,
+    pub revenge: f32,
+    pub cooldown: Timer,
+}
+
+pub fn parry_ability(
+    mut query: Query<
+        (
+            &mut ParryAbility,
+            &mut State,
+            &ActionState<PlayerActions>,
+            Entity,
+        ),
+        (With<Player>, Without<Stunned>),
+    >,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    let Ok((mut parry, mut state, action_state, entity)) = query.get_single_mut() else {
+        return;
+    };
+
+    parry.cooldown.tick(time.delta());
+
+    if !(state.equals(State::Idle) || state.equals(State::Walking)) {
+        return;
+    }
+
+    if !action_state.just_pressed(&PlayerActions::Parry) || !parry.cooldown.finished() {
+        return;
+    }
+
+    parry.cooldown.reset();
+    state.set(State::Attacking(parry.animation));
+
+    //Skips the ChargePhase, the AttackPhase is the parry window
+    commands.entity(entity).insert((
+        Parrying,
+        attack_phase(0., parry.window, parry.recover),
+        AttackPhase(Timer::from_seconds(parry.window, TimerMode::Once)),
+    ));
+}
+
+//A successful parry stuns the attacker and skips the recovery so the player can counter
+pub fn parry_success(
+    mut events: EventReader<ParryEvent>,
+    mut query: Query<(&ParryAbility, &mut State, &mut Revenge)>,
+    mut camera_query: Query<&mut Shake>,
+    mut status_writer: EventWriter<ApplyStatusEvent>,
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+) {
+    for event in events.read() {
+        let Ok((parry, mut state, mut revenge)) = query.get_mut(event.entity) else {
+            continue;
+        };
+
+        revenge.amount += parry.revenge;
+        state.set(State::Idle);
+
+        commands
+            .entity(event.entity)
+            .remove::<StateMachine>()
+            .remove::<AttackPhase>()
+            .remove::<Parrying>()
+            .try_insert(Invulnerable::new(parry.window));
+
+        if let Some(attacker) = event.attacker {
+            status_writer.send(ApplyStatusEvent {
+                entity: attacker,
+                effect: StatusEffectData {
+                    kind: StatusKind::Stun,
+                    duration: parry.stun,
+                    tick_rate: None,
+                    damage: None,
+                    slow: None,
+                    stacking: Stacking::Refresh,
+                    max_stacks: None,
+                },
+            });
+        }
+
+        if let Ok(mut shake) = camera_query.get_single_mut() {
+            shake.add_trauma(0.4);
+        }
+
+        commands.spawn(floating_text(
+            game_assets.font.clone(),
+            event.position,
+            "Parry!".to_string(),
+            Color::srgb(0.4, 0.8, 1.),
+            14.,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presses_are_remembered_for_the_window() {
+        let mut buffer = InputBuffer::new(0.25);
+        buffer.press(PlayerActions::Attack);
+        buffer.tick(Duration::from_secs_f32(0.2));
+
+        assert!(buffer.pending(PlayerActions::Attack));
+        assert!(!buffer.pending(PlayerActions::Dash));
+
+        buffer.tick(Duration::from_secs_f32(0.1));
+
+        assert!(!buffer.pending(PlayerActions::Attack));
+    }
+
+    #[test]
+    fn the_latest_press_wins() {
+        let mut buffer = InputBuffer::new(0.25);
+        buffer.press(PlayerActions::Attack);
+        buffer.tick(Duration::from_secs_f32(0.2));
+        buffer.press(PlayerActions::Dash);
+        buffer.tick(Duration::from_secs_f32(0.2));
+
+        assert!(buffer.pending(PlayerActions::Dash));
+        assert!(!buffer.pending(PlayerActions::Attack));
+    }
+
+    #[test]
+    fn consumed_presses_are_forgotten() {
+        let mut buffer = InputBuffer::new(0.25);
+        buffer.press(PlayerActions::Dash);
+        buffer.consume();
+
+        assert!(!buffer.pending(PlayerActions::Dash));
+    }
+}
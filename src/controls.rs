@@ -0,0 +1,418 @@
+use bevy::{input::gamepad::GamepadButton, prelude::*, utils::HashMap, window::PrimaryWindow};
+use bevy_egui::{egui, EguiInput, EguiSet};
+use bevy_persistent::prelude::*;
+use leafwing_input_manager::prelude::{GamepadStick, InputMap};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    player::{Player, PlayerActions},
+    GameState,
+};
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveDevice>()
+            .add_systems(
+                Update,
+                (
+                    apply_controls.run_if(resource_exists_and_changed::<Persistent<Controls>>),
+                    detect_active_device,
+                ),
+            )
+            .add_systems(
+                PreUpdate,
+                gamepad_menu_navigation
+                    .after(EguiSet::ProcessInput)
+                    .before(EguiSet::BeginFrame)
+                    .run_if(not(in_state(GameState::InGame))),
+            );
+    }
+}
+
+//Kind of input a binding slot accepts
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Device {
+    Keyboard,
+    Mouse,
+    Gamepad,
+}
+
+impl Device {
+    pub const ALL: [Self; 3] = [Self::Keyboard, Self::Mouse, Self::Gamepad];
+}
+
+//Last kind of device the player touched, prompts follow it
+#[derive(Resource, Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ActiveDevice {
+    #[default]
+    KeyboardMouse,
+    Gamepad,
+}
+
+//Button names as printed on an Xbox style gamepad
+fn gamepad_glyph(button: GamepadButtonType) -> String {
+    match button {
+        GamepadButtonType::South => "A".to_string(),
+        GamepadButtonType::East => "B".to_string(),
+        GamepadButtonType::West => "X".to_string(),
+        GamepadButtonType::North => "Y".to_string(),
+        GamepadButtonType::LeftTrigger => "LB".to_string(),
+        GamepadButtonType::RightTrigger => "RB".to_string(),
+        GamepadButtonType::LeftTrigger2 => "LT".to_string(),
+        GamepadButtonType::RightTrigger2 => "RT".to_string(),
+        GamepadButtonType::LeftThumb => "LS".to_string(),
+        GamepadButtonType::RightThumb => "RS".to_string(),
+        GamepadButtonType::Select => "Back".to_string(),
+        GamepadButtonType::Start => "Start".to_string(),
+        GamepadButtonType::DPadUp => "D-Pad Up".to_string(),
+        GamepadButtonType::DPadDown => "D-Pad Down".to_string(),
+        GamepadButtonType::DPadLeft => "D-Pad Left".to_string(),
+        GamepadButtonType::DPadRight => "D-Pad Right".to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+//Inputs bound to a single action, one per device
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Debug)]
+pub struct ActionBinding {
+    pub key: Option<KeyCode>,
+    pub mouse: Option<MouseButton>,
+    pub gamepad: Option<GamepadButtonType>,
+}
+
+impl ActionBinding {
+    pub fn label(&self, device: Device) -> Option<String> {
+        match device {
+            Device::Keyboard => self.key.map(|key| {
+                let name = format!("{:?}", key);
+                name.strip_prefix("Key")
+                    .or(name.strip_prefix("Digit"))
+                    .unwrap_or(&name)
+                    .to_string()
+            }),
+            Device::Mouse => self.mouse.map(|button| format!("Mouse {:?}", button)),
+            Device::Gamepad => self.gamepad.map(gamepad_glyph),
+        }
+    }
+
+    //Input shown in prompts for the device the player is using
+    pub fn prompt(&self, active: ActiveDevice) -> Option<String> {
+        match active {
+            ActiveDevice::KeyboardMouse => self
+                .label(Device::Keyboard)
+                .or_else(|| self.label(Device::Mouse)),
+            ActiveDevice::Gamepad => self.label(Device::Gamepad),
+        }
+    }
+
+    pub fn clear(&mut self, device: Device) {
+        match device {
+            Device::Keyboard => self.key = None,
+            Device::Mouse => self.mouse = None,
+            Device::Gamepad => self.gamepad = None,
+        }
+    }
+
+    fn take_input(&mut self, other: &Self, device: Device) {
+        match device {
+            Device::Keyboard => self.key = other.key,
+            Device::Mouse => self.mouse = other.mouse,
+            Device::Gamepad => self.gamepad = other.gamepad,
+        }
+    }
+
+    fn same_input(&self, other: &Self, device: Device) -> bool {
+        match device {
+            Device::Keyboard => self.key.is_some() && self.key == other.key,
+            Device::Mouse => self.mouse.is_some() && self.mouse == other.mouse,
+            Device::Gamepad => self.gamepad.is_some() && self.gamepad == other.gamepad,
+        }
+    }
+}
+
+//Player bindings, saved between runs
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+pub struct Controls {
+    pub bindings: HashMap<PlayerActions, ActionBinding>,
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        use PlayerActions::*;
+
+        let key = |key, gamepad| ActionBinding {
+            key: Some(key),
+            mouse: None,
+            gamepad: Some(gamepad),
+        };
+        let key_and_mouse = |key, mouse, gamepad| ActionBinding {
+            key: Some(key),
+            mouse: Some(mouse),
+            gamepad: Some(gamepad),
+        };
+
+        Self {
+            bindings: HashMap::from_iter([
+                (MoveUp, key(KeyCode::KeyW, GamepadButtonType::DPadUp)),
+                (MoveDown, key(KeyCode::KeyS, GamepadButtonType::DPadDown)),
+                (MoveLeft, key(KeyCode::KeyA, GamepadButtonType::DPadLeft)),
+                (MoveRight, key(KeyCode::KeyD, GamepadButtonType::DPadRight)),
+                (
+                    Attack,
+                    key_and_mouse(KeyCode::KeyJ, MouseButton::Left, GamepadButtonType::West),
+                ),
+                (
+                    SecondaryAttack,
+                    key_and_mouse(KeyCode::KeyK, MouseButton::Right, GamepadButtonType::North),
+                ),
+                (
+                    HeavyAttack,
+                    key_and_mouse(
+                        KeyCode::KeyL,
+                        MouseButton::Middle,
+                        GamepadButtonType::RightTrigger2,
+                    ),
+                ),
+                (Parry, key(KeyCode::KeyF, GamepadButtonType::LeftTrigger)),
+                (
+                    ActivateRevenge,
+                    key(KeyCode::KeyR, GamepadButtonType::LeftTrigger2),
+                ),
+                (Dash, key(KeyCode::Space, GamepadButtonType::South)),
+                (
+                    SwapWeapon,
+                    key(KeyCode::KeyQ, GamepadButtonType::RightTrigger),
+                ),
+                (Interact, key(KeyCode::KeyE, GamepadButtonType::East)),
+                (Map, key(KeyCode::KeyM, GamepadButtonType::Select)),
+            ]),
+        }
+    }
+}
+
+impl Controls {
+    pub fn binding(&self, action: PlayerActions) -> ActionBinding {
+        self.bindings.get(&action).copied().unwrap_or_default()
+    }
+
+    pub fn input_map(&self) -> InputMap<PlayerActions> {
+        let mut input_map = InputMap::default();
+
+        for (action, binding) in self.bindings.iter() {
+            if let Some(key) = binding.key {
+                input_map.insert(*action, key);
+            }
+
+            if let Some(button) = binding.mouse {
+                input_map.insert(*action, button);
+            }
+
+            if let Some(button) = binding.gamepad {
+                input_map.insert(*action, button);
+            }
+        }
+
+        //The sticks are analog only, so they aren't rebindable
+        input_map
+            .insert_dual_axis(PlayerActions::Move, GamepadStick::LEFT)
+            .insert_dual_axis(PlayerActions::Aim, GamepadStick::RIGHT);
+
+        input_map
+    }
+
+    //Binds actions added after the controls were saved to their default, true if any were missing
+    pub fn add_missing(&mut self) -> bool {
+        let saved = self.bindings.len();
+
+        for (action, binding) in Controls::default().bindings {
+            self.bindings.entry(action).or_insert(binding);
+        }
+
+        self.bindings.len() != saved
+    }
+
+    //Other actions bound to the same input on that device
+    pub fn conflicts(&self, action: PlayerActions, device: Device) -> Vec<PlayerActions> {
+        self.conflicts_with(action, self.binding(action), device)
+    }
+
+    fn conflicts_with(
+        &self,
+        action: PlayerActions,
+        binding: ActionBinding,
+        device: Device,
+    ) -> Vec<PlayerActions> {
+        PlayerActions::REBINDABLE
+            .into_iter()
+            .filter(|other| *other != action && binding.same_input(&self.binding(*other), device))
+            .collect()
+    }
+
+    //Actions that already used the new input get the one it replaces
+    pub fn rebind(&mut self, action: PlayerActions, device: Device, binding: ActionBinding) {
+        let old = self.binding(action);
+
+        for other in self.conflicts_with(action, binding, device) {
+            if let Some(other) = self.bindings.get_mut(&other) {
+                other.take_input(&old, device);
+            }
+        }
+
+        self.bindings.insert(action, binding);
+    }
+}
+
+fn apply_controls(
+    controls: Res<Persistent<Controls>>,
+    mut query: Query<&mut InputMap<PlayerActions>, With<Player>>,
+) {
+    for mut input_map in query.iter_mut() {
+        *input_map = controls.input_map();
+    }
+}
+
+fn detect_active_device(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    mut active: ResMut<ActiveDevice>,
+) {
+    const STICK_THRESHOLD: f32 = 0.5;
+
+    let stick_moved = gamepads.iter().any(|gamepad| {
+        [GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY]
+            .into_iter()
+            .any(|axis| {
+                axes.get(GamepadAxis::new(gamepad, axis))
+                    .is_some_and(|value| value.abs() > STICK_THRESHOLD)
+            })
+    });
+
+    if gamepad_buttons.get_just_pressed().next().is_some() || stick_moved {
+        active.set_if_neq(ActiveDevice::Gamepad);
+    } else if keys.get_just_pressed().next().is_some() || mouse.get_just_pressed().next().is_some()
+    {
+        active.set_if_neq(ActiveDevice::KeyboardMouse);
+    }
+}
+
+//egui only knows about the keyboard, so the gamepad is turned into focus navigation keys
+fn gamepad_menu_navigation(
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut egui_inputs: Query<&mut EguiInput, With<PrimaryWindow>>,
+) {
+    let Ok(mut input) = egui_inputs.get_single_mut() else {
+        return;
+    };
+
+    for button in gamepad_buttons.get_just_pressed() {
+        let (key, modifiers) = match button.button_type {
+            GamepadButtonType::DPadDown | GamepadButtonType::DPadRight => {
+                (egui::Key::Tab, egui::Modifiers::NONE)
+            }
+            GamepadButtonType::DPadUp | GamepadButtonType::DPadLeft => {
+                (egui::Key::Tab, egui::Modifiers::SHIFT)
+            }
+            GamepadButtonType::South => (egui::Key::Enter, egui::Modifiers::NONE),
+            _ => continue,
+        };
+
+        for pressed in [true, false] {
+            input.0.events.push(egui::Event::Key {
+                key,
+                physical_key: None,
+                pressed,
+                repeat: false,
+                modifiers,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_actions_get_their_default_binding() {
+        let mut controls = Controls::default();
+        controls.bindings.remove(&PlayerActions::Map);
+        controls.bindings.get_mut(&PlayerActions::Dash).unwrap().key = Some(KeyCode::ShiftLeft);
+
+        assert!(controls.add_missing());
+        assert_eq!(
+            controls.binding(PlayerActions::Map),
+            Controls::default().binding(PlayerActions::Map)
+        );
+        assert_eq!(
+            controls.binding(PlayerActions::Dash).key,
+            Some(KeyCode::ShiftLeft)
+        );
+        assert!(!controls.add_missing());
+    }
+
+    #[test]
+    fn default_bindings_have_no_conflicts() {
+        let controls = Controls::default();
+
+        for action in PlayerActions::REBINDABLE {
+            for device in Device::ALL {
+                assert!(controls.conflicts(action, device).is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn shared_inputs_are_conflicts_on_their_device_only() {
+        let mut controls = Controls::default();
+        controls.bindings.get_mut(&PlayerActions::Dash).unwrap().key = Some(KeyCode::KeyJ);
+
+        assert_eq!(
+            controls.conflicts(PlayerActions::Dash, Device::Keyboard),
+            vec![PlayerActions::Attack]
+        );
+        assert!(controls
+            .conflicts(PlayerActions::Dash, Device::Gamepad)
+            .is_empty());
+    }
+
+    #[test]
+    fn unbound_inputs_never_conflict() {
+        let mut controls = Controls::default();
+        controls
+            .bindings
+            .get_mut(&PlayerActions::Dash)
+            .unwrap()
+            .mouse = None;
+
+        assert!(controls
+            .conflicts(PlayerActions::Dash, Device::Mouse)
+            .is_empty());
+    }
+
+    #[test]
+    fn rebinding_to_a_used_input_swaps_them() {
+        let mut controls = Controls::default();
+        let mut binding = controls.binding(PlayerActions::Dash);
+        binding.key = Some(KeyCode::KeyJ);
+
+        controls.rebind(PlayerActions::Dash, Device::Keyboard, binding);
+
+        assert_eq!(
+            controls.binding(PlayerActions::Dash).key,
+            Some(KeyCode::KeyJ)
+        );
+        assert_eq!(
+            controls.binding(PlayerActions::Attack).key,
+            Some(KeyCode::Space)
+        );
+        assert_eq!(
+            controls.binding(PlayerActions::Attack).mouse,
+            Some(MouseButton::Left)
+        );
+    }
+}
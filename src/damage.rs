@@ -0,0 +1,335 @@
+use bevy::{math::Vec3Swizzles, prelude::*, utils::HashMap};
+use bevy_persistent::prelude::*;
+use serde::{Deserialize, Serialize};
+use turborand::{rng::Rng, TurboRand};
+
+use crate::{
+    feedback::{HitEvent, HitFeedback},
+    heal::{HealEvent, Lifesteal},
+    movement::easing::{EaseFunction, EaseTo},
+    player::Player,
+    statistics::Statistics,
+    stats::{Damage, Health, Revenge},
+    GameState,
+};
+
+pub struct DamagePlugin;
+
+impl Plugin for DamagePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>().add_systems(
+            Update,
+            (apply_damage, tick_invulnerability, flash_invulnerable)
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, Default, Reflect)]
+pub enum DamageKind {
+    #[default]
+    Physical,
+    Fire,
+    Poison,
+    Arcane,
+}
+
+impl DamageKind {
+    pub fn color(&self) -> Color {
+        match self {
+            DamageKind::Physical => Color::WHITE,
+            DamageKind::Fire => Color::srgb(1., 0.5, 0.1),
+            DamageKind::Poison => Color::srgb(0.5, 0.9, 0.2),
+            DamageKind::Arcane => Color::srgb(0.7, 0.4, 1.),
+        }
+    }
+}
+
+//Fraction of the damage ignored per kind, negative values are weaknesses
+#[derive(Component, Default, Clone, Debug, PartialEq)]
+pub struct Resistances(pub HashMap<DamageKind, f32>);
+
+impl Resistances {
+    pub fn get(&self, kind: DamageKind) -> f32 {
+        self.0.get(&kind).copied().unwrap_or_default().min(1.)
+    }
+}
+
+#[derive(Component)]
+pub struct CriticalStrike {
+    pub chance: f32,
+    pub multiplier: f32,
+}
+
+//Entities with this component ignore any hit, a timer of None lasts until removed
+#[derive(Component)]
+pub struct Invulnerable(pub Option<Timer>);
+
+impl Invulnerable {
+    pub fn new(duration: f32) -> Self {
+        Self(Some(Timer::from_seconds(duration, TimerMode::Once)))
+    }
+}
+
+//Takes no damage at all, toggled from the developer console
+#[derive(Component)]
+pub struct GodMode;
+
+//How an entity reacts after getting hit
+#[derive(Component, Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+pub struct HitReaction {
+    pub invulnerability: f32, // Seconds hits are ignored for
+    pub stun: f32,            // Seconds the entity can't act
+    pub knockback: f32,       // Distance it is pushed away
+}
+
+//Last entity that damaged this one, used to know who got the kill
+#[derive(Component)]
+pub struct LastAttacker(pub Entity);
+
+//Every source of damage should go through this event
+#[derive(Event)]
+pub struct DamageEvent {
+    pub attacker: Option<Entity>,
+    pub victim: Entity,
+    pub damage: Damage,
+    pub feedback: HitFeedback,
+    pub knockback: Option<(Vec2, f32)>, // Position the victim is pushed to if it survives, seconds
+}
+
+pub struct DamageResult {
+    pub amount: u32,
+    pub kind: DamageKind,
+    pub crit: bool,
+    pub resistance: f32,
+}
+
+pub fn calculate_damage(
+    damage: &Damage,
+    crit: Option<&CriticalStrike>,
+    resistances: Option<&Resistances>,
+    rand: &Rng,
+) -> DamageResult {
+    let mut amount = damage.amount as f32;
+
+    let crit_multiplier = crit
+        .filter(|crit| rand.f32() < crit.chance)
+        .map(|crit| crit.multiplier);
+
+    if let Some(multiplier) = crit_multiplier {
+        amount *= multiplier;
+    }
+
+    let resistance = resistances.map_or(0., |r| r.get(damage.kind));
+    amount *= 1. - resistance;
+
+    DamageResult {
+        amount: amount.round() as u32,
+        kind: damage.kind,
+        crit: crit_multiplier.is_some(),
+        resistance,
+    }
+}
+
+pub fn apply_damage(
+    mut events: EventReader<DamageEvent>,
+    mut victims: Query<
+        (&mut Health, &Transform, Option<&Resistances>, Has<Player>),
+        Without<GodMode>,
+    >,
+    mut attackers: Query<(
+        Option<&CriticalStrike>,
+        Option<&Lifesteal>,
+        Option<&mut Revenge>,
+        Has<Player>,
+    )>,
+    mut heal_writer: EventWriter<HealEvent>,
+    mut hit_writer: EventWriter<HitEvent>,
+    mut statistics: ResMut<Persistent<Statistics>>,
+    mut commands: Commands,
+) {
+    let rand = Rng::new();
+
+    for event in events.read() {
+        let Ok((mut health, transform, resistances, victim_is_player)) =
+            victims.get_mut(event.victim)
+        else {
+            continue;
+        };
+
+        let mut attacker = event.attacker.and_then(|a| attackers.get_mut(a).ok());
+        let crit = attacker.as_ref().and_then(|(crit, ..)| *crit);
+
+        let result = calculate_damage(&event.damage, crit, resistances, &rand);
+        let damage = Damage::new(result.amount).with_kind(result.kind);
+
+        health.damage(&damage);
+
+        //Corpses stay where they fell
+        if let Some((position, duration)) = event.knockback {
+            if health.current > 0 {
+                if let Some(mut ec) = commands.get_entity(event.victim) {
+                    ec.try_insert(EaseTo::new(position, EaseFunction::EaseOutExpo, duration));
+                }
+            }
+        }
+
+        if let Some(attacker) = event.attacker {
+            commands
+                .entity(event.victim)
+                .try_insert(LastAttacker(attacker));
+        }
+
+        if victim_is_player {
+            statistics.damage_taken += result.amount;
+        }
+
+        if let Some((_, lifesteal, revenge, attacker_is_player)) = attacker.as_mut() {
+            if *attacker_is_player {
+                statistics.damage_dealt += result.amount;
+            }
+
+            if let Some(revenge) = revenge {
+                revenge.amount += result.amount as f32 / 10.;
+            }
+
+            if let Some(lifesteal) = lifesteal {
+                heal_writer.send(HealEvent {
+                    entity: event.attacker.unwrap(),
+                    amount: (result.amount as f32 * lifesteal.0) as u32,
+                });
+            }
+        }
+
+        hit_writer.send(HitEvent {
+            attacker: event.attacker,
+            victim: event.victim,
+            amount: result.amount,
+            kind: result.kind,
+            crit: result.crit,
+            resisted: result.resistance > 0.,
+            position: transform.translation.xy(),
+            feedback: event.feedback,
+        });
+    }
+}
+
+fn tick_invulnerability(
+    mut query: Query<(&mut Invulnerable, Entity)>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    for (mut invulnerable, entity) in query.iter_mut() {
+        let Some(timer) = &mut invulnerable.0 else {
+            continue;
+        };
+
+        timer.tick(time.delta());
+
+        if timer.finished() {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    }
+}
+
+fn flash_invulnerable(
+    mut query: Query<&mut Sprite, With<Invulnerable>>,
+    mut sprites: Query<&mut Sprite, Without<Invulnerable>>,
+    mut removals: RemovedComponents<Invulnerable>,
+    time: Res<Time>,
+) {
+    const FLASH_RATE: f32 = 12.;
+
+    let visible = ((time.elapsed_seconds() * FLASH_RATE) as u32).is_multiple_of(2);
+
+    for mut sprite in query.iter_mut() {
+        sprite.color.set_alpha(if visible { 1. } else { 0.3 });
+    }
+
+    for entity in removals.read() {
+        if let Ok(mut sprite) = sprites.get_mut(entity) {
+            sprite.color.set_alpha(1.);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use turborand::SeededCore;
+
+    use super::*;
+
+    fn resistances(kind: DamageKind, value: f32) -> Resistances {
+        Resistances(HashMap::from([(kind, value)]))
+    }
+
+    #[test]
+    fn resistances_reduce_damage_of_their_kind() {
+        let rand = Rng::with_seed(0);
+        let fire = resistances(DamageKind::Fire, 0.5);
+
+        let result = calculate_damage(
+            &Damage::new(100).with_kind(DamageKind::Fire),
+            None,
+            Some(&fire),
+            &rand,
+        );
+        assert_eq!(result.amount, 50);
+        assert_eq!(result.resistance, 0.5);
+
+        let result = calculate_damage(&Damage::new(100), None, Some(&fire), &rand);
+        assert_eq!(result.amount, 100);
+    }
+
+    #[test]
+    fn weaknesses_increase_damage() {
+        let rand = Rng::with_seed(0);
+        let poison = resistances(DamageKind::Poison, -0.5);
+
+        let result = calculate_damage(
+            &Damage::new(100).with_kind(DamageKind::Poison),
+            None,
+            Some(&poison),
+            &rand,
+        );
+        assert_eq!(result.amount, 150);
+    }
+
+    #[test]
+    fn resistances_never_heal() {
+        let rand = Rng::with_seed(0);
+        let arcane = resistances(DamageKind::Arcane, 2.);
+
+        let result = calculate_damage(
+            &Damage::new(100).with_kind(DamageKind::Arcane),
+            None,
+            Some(&arcane),
+            &rand,
+        );
+        assert_eq!(result.amount, 0);
+    }
+
+    #[test]
+    fn crits_scale_before_resistances() {
+        let rand = Rng::with_seed(0);
+        let fire = resistances(DamageKind::Fire, 0.5);
+        let always = CriticalStrike {
+            chance: 1.,
+            multiplier: 2.,
+        };
+        let never = CriticalStrike {
+            chance: 0.,
+            multiplier: 2.,
+        };
+
+        let damage = Damage::new(100).with_kind(DamageKind::Fire);
+
+        let result = calculate_damage(&damage, Some(&always), Some(&fire), &rand);
+        assert!(result.crit);
+        assert_eq!(result.amount, 100);
+
+        let result = calculate_damage(&damage, Some(&never), Some(&fire), &rand);
+        assert!(!result.crit);
+        assert_eq!(result.amount, 50);
+    }
+}
@@ -0,0 +1,320 @@
+use std::f32::consts::TAU;
+
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_persistent::prelude::*;
+use bevy_rapier2d::prelude::Collider;
+use bevy_spritesheet_animation::prelude::{AnimationEvent, SpritesheetAnimation};
+use leafwing_input_manager::prelude::ActionState;
+use serde::{Deserialize, Serialize};
+use turborand::{rng::Rng, TurboRand};
+
+use crate::{
+    animation::Animations,
+    attack::{Damageable, Lifetime},
+    boss::Boss,
+    controller::Controlled,
+    damage::{apply_damage, Invulnerable, LastAttacker},
+    effects::floating_text,
+    enemy::{state_machine, Enemy},
+    floor::{BossKilledEvent, EnemyKilledEvent, RunEndedEvent},
+    game_states::loading::GameAssets,
+    helper::Shake,
+    movement::movement::Velocity,
+    player::{Player, PlayerActions},
+    state::State,
+    statistics::Statistics,
+    stats::{Health, XP},
+    GameState, InRun,
+};
+
+pub struct DeathPlugin;
+
+impl Plugin for DeathPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                death_system.after(apply_damage),
+                player_death.after(apply_damage),
+                dying,
+                death_animation_end,
+            )
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+//How an enemy dies, set per enemy and boss in the manifests
+#[derive(Component, Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+#[serde(default)]
+pub struct DeathEffects {
+    pub duration: f32,  // Seconds before despawning when there is no death animation
+    pub fade: bool,     // Fades out while dying
+    pub shake: f32,     // Trauma added to the camera, from 0 to 1
+    pub particles: u32, // Particles spawned when it dies
+}
+
+impl Default for DeathEffects {
+    fn default() -> Self {
+        Self {
+            duration: 0.4,
+            fade: true,
+            shake: 0.15,
+            particles: 12,
+        }
+    }
+}
+
+//Dead enemy that can't act or be hit anymore, despawned when its death is over
+#[derive(Component)]
+pub struct Dying {
+    timer: Option<Timer>, // None while the death animation plays
+    fade: bool,
+}
+
+//One-time revive, used up instead of dying
+#[derive(Component)]
+pub struct Revive {
+    pub health: f32, // Part of the max health restored
+}
+
+const DEATH_ANIMATION: &str = "death";
+const PLAYER_DEATH_TIME: f32 = 2.;
+const REVIVE_INVULNERABILITY: f32 = 2.;
+
+pub fn death_system(
+    mut commands: Commands,
+    mut query: Query<
+        (
+            Entity,
+            &Health,
+            &Transform,
+            Option<&XP>,
+            Option<&Enemy>,
+            Option<&Boss>,
+            Option<&LastAttacker>,
+            Option<&DeathEffects>,
+            Option<&Animations>,
+            Option<&mut SpritesheetAnimation>,
+        ),
+        (Without<Dying>, Without<Player>),
+    >,
+    mut camera_query: Query<&mut Shake>,
+    mut enemy_kill_writer: EventWriter<EnemyKilledEvent>,
+    mut boss_kill_writer: EventWriter<BossKilledEvent>,
+) {
+    for (
+        entity,
+        health,
+        transform,
+        xp,
+        enemy,
+        boss,
+        last_attacker,
+        effects,
+        animations,
+        spritesheet,
+    ) in query.iter_mut()
+    {
+        if health.current != 0 {
+            continue;
+        }
+
+        let position = transform.translation.xy();
+
+        if let Some(enemy) = enemy {
+            enemy_kill_writer.send(EnemyKilledEvent {
+                entity,
+                name: enemy.0.clone(),
+                position,
+                xp: xp.map_or(0, |xp| xp.amount),
+                killer: last_attacker.map(|attacker| attacker.0),
+            });
+        }
+
+        if let Some(boss) = boss {
+            boss_kill_writer.send(BossKilledEvent(boss.0.clone()));
+        }
+
+        let effects = effects.copied().unwrap_or_default();
+
+        if let Ok(mut shake) = camera_query.get_single_mut() {
+            shake.add_trauma(effects.shake);
+        }
+
+        spawn_death_particles(&mut commands, position, effects.particles);
+
+        state_machine::stop_behaviour(&mut commands, entity);
+        commands.entity(entity).remove::<(Collider, Damageable)>();
+
+        let death_animation =
+            animations.and_then(|animations| animations.0.get(DEATH_ANIMATION).copied());
+
+        //Entities without a death animation just wait for the manifest duration
+        let timer = match (death_animation, spritesheet) {
+            (Some(animation), Some(mut spritesheet)) => {
+                spritesheet.switch(animation);
+                None
+            }
+            _ => Some(Timer::from_seconds(effects.duration, TimerMode::Once)),
+        };
+
+        commands.entity(entity).insert(Dying {
+            timer,
+            fade: effects.fade,
+        });
+    }
+}
+
+//The player stops responding and the run ends once the death sequence is over
+fn player_death(
+    mut commands: Commands,
+    mut query: Query<
+        (
+            Entity,
+            &mut Health,
+            &mut State,
+            &mut Controlled,
+            &Transform,
+            Option<&Revive>,
+        ),
+        (With<Player>, Without<Dying>),
+    >,
+    mut camera_query: Query<&mut Shake>,
+    mut statistics: ResMut<Persistent<Statistics>>,
+    game_assets: Res<GameAssets>,
+) {
+    let Ok((entity, mut health, mut state, mut controlled, transform, revive)) =
+        query.get_single_mut()
+    else {
+        return;
+    };
+
+    if health.current != 0 {
+        return;
+    }
+
+    let position = transform.translation.xy();
+
+    if let Some(revive) = revive {
+        health.current = ((health.max as f32 * revive.health).round() as u32).max(1);
+
+        commands
+            .entity(entity)
+            .remove::<Revive>()
+            .try_insert(Invulnerable::new(REVIVE_INVULNERABILITY));
+        commands.spawn(floating_text(
+            game_assets.font.clone(),
+            position,
+            "Revived!".to_string(),
+            Color::srgb(1., 0.85, 0.3),
+            12.,
+        ));
+        return;
+    }
+
+    statistics.deaths += 1;
+
+    if let Ok(mut shake) = camera_query.get_single_mut() {
+        shake.add_trauma(0.8);
+    }
+
+    spawn_death_particles(&mut commands, position, 30);
+
+    state.set(State::Dying);
+    controlled.move_to = None;
+
+    commands
+        .entity(entity)
+        .remove::<(ActionState<PlayerActions>, Collider, Damageable)>()
+        .insert(Dying {
+            timer: Some(Timer::from_seconds(PLAYER_DEATH_TIME, TimerMode::Once)),
+            fade: false,
+        });
+}
+
+fn spawn_death_particles(commands: &mut Commands, position: Vec2, count: u32) {
+    const SPEED: f32 = 50.;
+    const SIZE: f32 = 3.;
+    const LIFETIME: f32 = 0.5;
+
+    let rand = Rng::new();
+
+    for _ in 0..count {
+        let direction = Vec2::from_angle(rand.f32() * TAU);
+        let speed = SPEED * (0.5 + rand.f32());
+
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(0.6, 0.05, 0.05),
+                    custom_size: Some(Vec2::splat(SIZE)),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(400.)),
+                ..default()
+            },
+            Velocity(direction * speed, false),
+            Lifetime(Timer::from_seconds(LIFETIME, TimerMode::Once)),
+            StateScoped(InRun),
+        ));
+    }
+}
+
+fn dying(
+    mut query: Query<(&mut Dying, Option<&mut Sprite>, Has<Player>, Entity)>,
+    mut run_writer: EventWriter<RunEndedEvent>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    for (mut dying, sprite, is_player, entity) in query.iter_mut() {
+        let fade = dying.fade;
+
+        let Some(timer) = dying.timer.as_mut() else {
+            continue;
+        };
+
+        timer.tick(time.delta());
+
+        //The player stays on screen behind the run summary
+        if is_player {
+            if timer.just_finished() {
+                run_writer.send(RunEndedEvent { victory: false });
+            }
+
+            if let Some(mut sprite) = sprite {
+                let dark = 1. - timer.fraction() * 0.6;
+                let alpha = sprite.color.alpha();
+                sprite.color = Color::srgba(dark, dark * 0.5, dark * 0.5, alpha);
+            }
+
+            continue;
+        }
+
+        if timer.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        if let (true, Some(mut sprite)) = (fade, sprite) {
+            sprite.color.set_alpha(1. - timer.fraction());
+        }
+    }
+}
+
+//Death animations play once, the entity goes away when they end
+fn death_animation_end(
+    mut events: EventReader<AnimationEvent>,
+    query: Query<&Dying>,
+    mut commands: Commands,
+) {
+    for event in events.read() {
+        let AnimationEvent::AnimationEnd { entity, .. } = event else {
+            continue;
+        };
+
+        if query.get(*entity).is_ok_and(|dying| dying.timer.is_none()) {
+            commands.entity(*entity).despawn_recursive();
+        }
+    }
+}
@@ -1,5 +1,13 @@
-use crate::{game_states::loading::GameAssets, sorting::FeetOffset};
-use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::{
+    attack::Lifetime,
+    game_states::loading::GameAssets,
+    movement::easing::{EaseFunction, EaseTo},
+    sorting::FeetOffset,
+    InRun,
+};
+use bevy::{prelude::*, text::JustifyText};
 
 #[derive(Component)]
 pub struct Shadow;
@@ -20,3 +28,29 @@ pub fn spawn_shadows(
         });
     }
 }
+
+//Text that floats up from a position and disappears (damage, healing, etc.)
+pub fn floating_text(
+    font: Handle<Font>,
+    position: Vec2,
+    text: String,
+    color: Color,
+    font_size: f32,
+) -> (Text2dBundle, EaseTo, Lifetime, StateScoped<InRun>) {
+    let text_style = TextStyle {
+        font,
+        font_size,
+        color,
+    };
+
+    (
+        Text2dBundle {
+            text: Text::from_section(text, text_style).with_justify(JustifyText::Center),
+            transform: Transform::from_translation(position.extend(500.)),
+            ..Default::default()
+        },
+        EaseTo::new(position + Vec2::new(0., 20.), EaseFunction::EaseOutExpo, 1.),
+        Lifetime(Timer::new(Duration::from_secs_f32(1.), TimerMode::Once)),
+        StateScoped(InRun),
+    )
+}
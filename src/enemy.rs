@@ -8,6 +8,8 @@ use bevy_spritesheet_animation::prelude::SpritesheetAnimation;
 use seldom_state::prelude::StateMachine;
 
 use crate::animation::Animations;
+use crate::damage::Resistances;
+use crate::death::DeathEffects;
 use crate::effects::Shadow;
 use crate::manifest::enemy::EnemyData;
 use crate::sorting::{self, FeetOffset, YSort};
@@ -28,7 +30,11 @@ impl Plugin for EnemyBehaviourPlugin {
 }
 
 #[derive(Component)]
-pub struct Enemy(String);
+pub struct Enemy(pub String);
+
+//Shows the enemy's name above it at all times
+#[derive(Component)]
+pub struct NamePlate;
 
 #[derive(Bundle)]
 pub struct EnemyBundle {
@@ -37,6 +43,7 @@ pub struct EnemyBundle {
     pub atlas: TextureAtlas,
     pub stats: StatsBundle,
     pub damageable: Damageable,
+    pub resistances: Resistances,
     pub animations: Animations,
     pub animation: SpritesheetAnimation,
     pub rigid_body: RigidBody,
@@ -47,6 +54,7 @@ pub struct EnemyBundle {
     pub ysort: YSort,
     pub shadow: Shadow,
     pub feet_offset: FeetOffset,
+    pub death: DeathEffects,
     // finding_player: FindingPLayer,
     state_machine: StateMachine,
 }
@@ -76,12 +84,13 @@ impl EnemyBundle {
                 cooldown: Cooldown::new(data.cooldown),
             },
             damageable: Damageable,
+            resistances: Resistances(data.resistances.clone()),
             animation: SpritesheetAnimation::from_id(
                 *data
                     .animations
                     .0
-                    .values()
-                    .next()
+                    .get("idle")
+                    .or_else(|| data.animations.0.values().next())
                     .expect(format!("No animations for {}", data.name).as_str()),
             ),
             animations: data.animations.clone(),
@@ -94,6 +103,7 @@ impl EnemyBundle {
             ysort: YSort(sorting::ENTITIES_LAYER),
             feet_offset: FeetOffset(data.feet_offset.unwrap_or_default()),
             shadow: Shadow,
+            death: data.death,
         }
     }
 }
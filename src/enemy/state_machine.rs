@@ -14,12 +14,15 @@ use turborand::rng::Rng;
 use turborand::TurboRand;
 
 use crate::attack::SpawnEnemyAttack;
+use crate::boss::Boss;
 use crate::enemy::Enemy;
+use crate::manifest::boss::BossManifest;
 use crate::manifest::enemy::EnemyManifest;
 use crate::manifest::AttackData;
 use crate::movement::movement::{Follow, Velocity};
 use crate::player::Player;
 use crate::stats::{Cooldown, Damage};
+use crate::status::Stunned;
 use crate::GameState;
 
 pub(crate) fn register(app: &mut App) {
@@ -83,9 +86,23 @@ struct Attack;
 #[derive(Component)]
 struct IdleDuration(Timer);
 
+//Removes the state machine and whatever its states left behind, so the enemy stops acting
+pub(crate) fn stop_behaviour(commands: &mut Commands, entity: Entity) {
+    commands.entity(entity).remove::<(
+        StateMachine,
+        Idle,
+        Wander,
+        FollowPlayer,
+        Attack,
+        IdleDuration,
+        Follow,
+        Velocity,
+    )>();
+}
+
 fn idle(
     mut commands: Commands,
-    mut enemies: Query<(Entity, Option<&mut IdleDuration>), With<Idle>>,
+    mut enemies: Query<(Entity, Option<&mut IdleDuration>), (With<Idle>, Without<Stunned>)>,
     time: Res<Time>,
 ) {
     let rand = Rng::new();
@@ -109,7 +126,7 @@ fn idle(
 }
 
 fn wander(
-    enemies: Query<(Entity, Option<&Velocity>), With<Wander>>,
+    enemies: Query<(Entity, Option<&Velocity>), (With<Wander>, Without<Stunned>)>,
     mut commands: Commands,
     mut timers: Local<HashMap<Entity, f32>>,
     time: Res<Time>,
@@ -144,7 +161,7 @@ fn wander(
 }
 
 fn follow_player(
-    enemies: Query<Entity, (With<Enemy>, With<FollowPlayer>)>,
+    enemies: Query<Entity, (With<Enemy>, With<FollowPlayer>, Without<Stunned>)>,
     player: Query<Entity, With<Player>>,
     follows: Query<&Follow, With<Enemy>>,
     mut commands: Commands,
@@ -167,37 +184,66 @@ fn follow_player(
 fn attack_player(
     player_query: Query<&Transform, With<Player>>,
     mut event: EventWriter<SpawnEnemyAttack>,
-    mut enemies: Query<(Entity, &Enemy, &Transform, &Damage, &mut Cooldown), With<Attack>>,
+    mut enemies: Query<
+        (
+            Entity,
+            Option<&Enemy>,
+            Option<&Boss>,
+            &Transform,
+            &Damage,
+            &mut Cooldown,
+        ),
+        (With<Attack>, Without<Stunned>),
+    >,
     mut commands: Commands,
     mut durations: Local<HashMap<Entity, f32>>,
     enemy_manifest: Res<EnemyManifest>,
+    boss_manifest: Res<BossManifest>,
     time: Res<Time>,
 ) {
-    for (entity, enemy, transform, damage, mut cooldown) in enemies.iter_mut() {
+    for (entity, enemy, boss, transform, damage, mut cooldown) in enemies.iter_mut() {
         if !durations.contains_key(&entity) && cooldown.is_ready() {
             if let Ok(player) = player_query.get_single() {
                 let direction = (player.translation - transform.translation)
                     .xy()
                     .normalize();
 
-                let enemy_data = enemy_manifest
-                    .enemies
-                    .get(&Id::from_name(enemy.0.as_str()))
-                    .unwrap();
+                let attack = match (enemy, boss) {
+                    (Some(enemy), _) => enemy_manifest
+                        .enemies
+                        .get(&Id::from_name(enemy.0.as_str()))
+                        .map(|data| (data.attack.clone(), data.hitbox)),
+                    (None, Some(boss)) => boss_manifest
+                        .bosses
+                        .get(&Id::from_name(boss.0.as_str()))
+                        .and_then(|data| data.attack.clone().map(|attack| (attack, data.hitbox))),
+                    (None, None) => None,
+                };
+
+                //Bosses without an attack go back to idle
+                let Some((attack, hitbox)) = attack else {
+                    commands.entity(entity).insert(Done::Success);
+                    continue;
+                };
 
-                let duration = match enemy_data.attack {
-                    AttackData::Melee { duration, .. } => duration,
-                    AttackData::Ranged { duration, .. } => duration,
+                let duration = match &attack {
+                    AttackData::Melee { duration, .. } => *duration,
+                    AttackData::Ranged {
+                        duration, pattern, ..
+                    } => *duration + pattern.burst_time(),
+                    AttackData::Area { area, .. } => area.attack_time(),
                 };
 
                 durations.insert(entity, duration);
 
                 event.send(SpawnEnemyAttack {
-                    data: enemy_data.attack.clone(),
+                    data: attack,
                     damage: *damage,
                     direction,
                     position: transform.translation,
-                    enemy_size: enemy_data.hitbox,
+                    enemy_size: hitbox,
+                    attacker: entity,
+                    target: player.translation.xy(),
                 });
                 cooldown.reset();
             }
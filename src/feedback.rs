@@ -0,0 +1,280 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use bevy_persistent::prelude::*;
+use serde::{Deserialize, Serialize};
+use turborand::{rng::Rng, TurboRand};
+
+use crate::{
+    attack::Lifetime, damage::DamageKind, effects::floating_text, game_states::loading::GameAssets,
+    helper::Shake, movement::movement::Velocity, settings::Settings, GameState, InRun,
+};
+
+pub struct FeedbackPlugin;
+
+impl Plugin for FeedbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<HitEvent>()
+            .init_resource::<HitStop>()
+            .add_systems(
+                Update,
+                (
+                    hit_stop,
+                    tick_hit_stop,
+                    hit_shake,
+                    hit_flash,
+                    tick_hit_flash,
+                    hit_particles,
+                    damage_numbers,
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(OnExit(GameState::InGame), reset_hit_stop);
+    }
+}
+
+//How strong the reaction to a hit is, set per attack in the manifests
+#[derive(Component, Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Reflect)]
+#[serde(default)]
+pub struct HitFeedback {
+    pub hit_stop: f32,  // Seconds the game is slowed down for
+    pub shake: f32,     // Trauma added to the camera, from 0 to 1
+    pub flash: f32,     // Seconds the victim flashes white
+    pub particles: u32, // Particles spawned on impact
+    pub numbers: bool,  // Show the damage number
+}
+
+impl Default for HitFeedback {
+    fn default() -> Self {
+        Self {
+            hit_stop: 0.04,
+            shake: 0.25,
+            flash: 0.1,
+            particles: 6,
+            numbers: true,
+        }
+    }
+}
+
+impl HitFeedback {
+    //Used for damage over time, which would be too noisy otherwise
+    pub const TICK: Self = Self {
+        hit_stop: 0.,
+        shake: 0.,
+        flash: 0.,
+        particles: 0,
+        numbers: true,
+    };
+}
+
+//Sent after damage is applied, every reaction to a hit should read this
+#[derive(Event)]
+pub struct HitEvent {
+    pub attacker: Option<Entity>,
+    pub victim: Entity,
+    pub amount: u32,
+    pub kind: DamageKind,
+    pub crit: bool,
+    pub resisted: bool,
+    pub position: Vec2,
+    pub feedback: HitFeedback,
+}
+
+#[derive(Resource, Default)]
+pub struct HitStop(Option<Timer>);
+
+#[derive(Component)]
+pub struct HitFlash {
+    timer: Timer,
+    pub color: Option<Color>, // Tint the sprite had before flashing, restored once it ends
+}
+
+const HIT_STOP_SPEED: f32 = 0.05;
+const CRIT_MULTIPLIER: f32 = 1.5;
+
+fn hit_stop(
+    mut events: EventReader<HitEvent>,
+    mut hit_stop: ResMut<HitStop>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    let duration = events
+        .read()
+        .map(|event| {
+            if event.crit {
+                event.feedback.hit_stop * CRIT_MULTIPLIER
+            } else {
+                event.feedback.hit_stop
+            }
+        })
+        .fold(0., f32::max);
+
+    if duration <= 0. {
+        return;
+    }
+
+    //Don't shorten a longer hit-stop that is already running
+    if let Some(timer) = &hit_stop.0 {
+        if timer.remaining_secs() >= duration {
+            return;
+        }
+    }
+
+    hit_stop.0 = Some(Timer::from_seconds(duration, TimerMode::Once));
+    time.set_relative_speed(HIT_STOP_SPEED);
+}
+
+//Uses real time as the virtual one is the one being slowed down
+fn tick_hit_stop(
+    mut hit_stop: ResMut<HitStop>,
+    mut time: ResMut<Time<Virtual>>,
+    real_time: Res<Time<Real>>,
+) {
+    let Some(timer) = &mut hit_stop.0 else {
+        return;
+    };
+
+    timer.tick(real_time.delta());
+
+    if timer.finished() {
+        hit_stop.0 = None;
+        time.set_relative_speed(1.);
+    }
+}
+
+fn reset_hit_stop(mut hit_stop: ResMut<HitStop>, mut time: ResMut<Time<Virtual>>) {
+    hit_stop.0 = None;
+    time.set_relative_speed(1.);
+}
+
+fn hit_shake(mut events: EventReader<HitEvent>, mut camera_query: Query<&mut Shake>) {
+    let Ok(mut shake) = camera_query.get_single_mut() else {
+        events.clear();
+        return;
+    };
+
+    for event in events.read() {
+        let trauma = if event.crit {
+            event.feedback.shake * CRIT_MULTIPLIER
+        } else {
+            event.feedback.shake
+        };
+
+        shake.add_trauma(trauma);
+    }
+}
+
+fn hit_flash(
+    mut events: EventReader<HitEvent>,
+    mut query: Query<&mut HitFlash>,
+    mut commands: Commands,
+) {
+    for event in events.read() {
+        if event.feedback.flash <= 0. {
+            continue;
+        }
+
+        let timer = Timer::from_seconds(event.feedback.flash, TimerMode::Once);
+
+        //Flashing again keeps the color saved by the first flash
+        if let Ok(mut flash) = query.get_mut(event.victim) {
+            flash.timer = timer;
+        } else if let Some(mut ec) = commands.get_entity(event.victim) {
+            ec.try_insert(HitFlash { timer, color: None });
+        }
+    }
+}
+
+//Sprites are tinted by multiplying, so a color over 1 pushes them towards white
+fn tick_hit_flash(
+    mut query: Query<(&mut HitFlash, &mut Sprite, Entity)>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    for (mut flash, mut sprite, entity) in query.iter_mut() {
+        flash.timer.tick(time.delta());
+
+        let alpha = sprite.color.alpha();
+        let color = *flash.color.get_or_insert(sprite.color);
+
+        if flash.timer.finished() {
+            sprite.color = color.with_alpha(alpha);
+            commands.entity(entity).remove::<HitFlash>();
+        } else {
+            sprite.color = Color::linear_rgba(10., 10., 10., alpha);
+        }
+    }
+}
+
+fn hit_particles(mut events: EventReader<HitEvent>, mut commands: Commands) {
+    const SPEED: f32 = 60.;
+    const SIZE: f32 = 2.;
+    const LIFETIME: f32 = 0.3;
+
+    let rand = Rng::new();
+
+    for event in events.read() {
+        let count = if event.crit {
+            event.feedback.particles * 2
+        } else {
+            event.feedback.particles
+        };
+
+        for _ in 0..count {
+            let direction = Vec2::from_angle(rand.f32() * TAU);
+            let speed = SPEED * (0.5 + rand.f32());
+
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: event.kind.color(),
+                        custom_size: Some(Vec2::splat(SIZE)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(event.position.extend(400.)),
+                    ..default()
+                },
+                Velocity(direction * speed, false),
+                Lifetime(Timer::from_seconds(LIFETIME, TimerMode::Once)),
+                StateScoped(InRun),
+            ));
+        }
+    }
+}
+
+fn damage_numbers(
+    mut events: EventReader<HitEvent>,
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    settings: Res<Persistent<Settings>>,
+) {
+    if !settings.damage_numbers {
+        events.clear();
+        return;
+    }
+
+    for event in events.read() {
+        if !event.feedback.numbers {
+            continue;
+        }
+
+        let (text, size, color) = if event.crit {
+            (
+                format!("-{}!", event.amount),
+                18.,
+                Color::srgb(1., 0.85, 0.2),
+            )
+        } else if event.resisted {
+            (format!("-{}", event.amount), 9., Color::srgb(0.6, 0.6, 0.6))
+        } else {
+            (format!("-{}", event.amount), 12., event.kind.color())
+        };
+
+        commands.spawn(floating_text(
+            game_assets.font.clone(),
+            event.position,
+            text,
+            color,
+            size,
+        ));
+    }
+}
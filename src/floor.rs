@@ -1,22 +1,19 @@
 use std::collections::BTreeMap;
 use std::time::Duration;
 
-use bevy::hierarchy::DespawnRecursiveExt;
-use bevy::input::ButtonInput;
 use bevy::prelude::{
-    in_state, Camera, Event, IntoSystemConfigs, Query, Transform, Update, With, Without, World,
+    in_state, Camera, Event, IntoSystemConfigs, Query, StateScoped, Transform, Update, With,
+    Without, World,
 };
 use bevy::time::Timer;
 use bevy::{
     math::Vec2,
-    prelude::{
-        App, Commands, Entity, EventReader, EventWriter, KeyCode, Plugin, Res, ResMut, Resource,
-    },
+    prelude::{App, Commands, Entity, EventReader, EventWriter, Plugin, Res, ResMut, Resource},
 };
 use leafwing_manifest::manifest::Manifest;
 use noisy_bevy::simplex_noise_2d;
 use turborand::rng::Rng;
-use turborand::TurboRand;
+use turborand::{SeededCore, TurboRand};
 
 use crate::boss::BossBundle;
 use crate::enemy::state_machine::Idle;
@@ -27,7 +24,10 @@ use crate::map::generation::open_level_portal;
 use crate::map::walkable::travel_through_portal;
 use crate::player::Player;
 use crate::ui::boss::DomainName;
-use crate::{enemy::EnemyBundle, GameState};
+use crate::{
+    enemy::{EnemyBundle, NamePlate},
+    GameState, InRun,
+};
 
 #[derive(Default, Resource)]
 pub struct FloorResource {
@@ -35,6 +35,17 @@ pub struct FloorResource {
     pub domain: Option<DomainData>,
     pub enemies: Vec<Entity>,
     pub boss: Option<Entity>,
+    pub seed: Option<u64>, // Makes every floor of the run generate the same way
+}
+
+impl FloorResource {
+    //Random generator for the current floor, seeded per floor when the run has a seed
+    pub fn rng(&self) -> Rng {
+        match self.seed {
+            Some(seed) => Rng::with_seed(seed.wrapping_add(self.floor as u64)),
+            None => Rng::new(),
+        }
+    }
 }
 
 //Floor Generation Events
@@ -46,11 +57,23 @@ pub struct SpawnFloorEntitiesEvent {
     pub spawnable_pos: Vec<Vec2>,
     pub player_pos: Vec2,
     pub portal_pos: Vec2,
+    pub room_centers: Vec<Vec2>, // Centers of the rooms between the start and the portal
 }
 
 //Floor Clearing Events
+
+//Sent once when an enemy starts dying, the entity is despawned after its death animation
 #[derive(Event)]
-pub struct EnemyKilledEvent(pub Entity); // Entity killed
+pub struct EnemyKilledEvent {
+    pub entity: Entity,
+    pub name: String,
+    pub position: Vec2,
+    pub xp: u32,
+    pub killer: Option<Entity>,
+}
+
+#[derive(Event)]
+pub struct BossKilledEvent(pub String); // Boss name
 
 #[derive(Event)]
 pub struct FloorClearedEvent; // All enemies killed
@@ -58,6 +81,12 @@ pub struct FloorClearedEvent; // All enemies killed
 #[derive(Event)]
 pub struct TriggerNextFloorEvent; // Player triggered next level
 
+//Run Events
+#[derive(Event)]
+pub struct RunEndedEvent {
+    pub victory: bool, // Player cleared every domain
+}
+
 pub struct FloorPlugin;
 
 impl Plugin for FloorPlugin {
@@ -66,8 +95,10 @@ impl Plugin for FloorPlugin {
             .add_event::<GenerateFloorEvent>()
             .add_event::<SpawnFloorEntitiesEvent>()
             .add_event::<EnemyKilledEvent>()
+            .add_event::<BossKilledEvent>()
             .add_event::<FloorClearedEvent>()
             .add_event::<TriggerNextFloorEvent>()
+            .add_event::<RunEndedEvent>()
             .add_systems(
                 Update,
                 (
@@ -77,7 +108,6 @@ impl Plugin for FloorPlugin {
                     spawn_enemies,
                     spawn_boss,
                     generate_floor,
-                    keymap_generate,
                     open_level_portal,
                     travel_through_portal,
                 )
@@ -86,15 +116,6 @@ impl Plugin for FloorPlugin {
     }
 }
 
-fn keymap_generate(
-    keys: Res<ButtonInput<KeyCode>>,
-    mut writer: EventWriter<TriggerNextFloorEvent>,
-) {
-    if keys.just_pressed(KeyCode::ControlLeft) {
-        writer.send(TriggerNextFloorEvent);
-    }
-}
-
 fn new_domain_trigger(
     mut commands: Commands,
     mut event: EventReader<GenerateFloorEvent>,
@@ -106,9 +127,12 @@ fn new_domain_trigger(
 
     if let Some(domain) = &floor.domain {
         if floor.floor == domain.floors.0 {
-            commands.spawn(DomainName(
-                domain.name.clone(),
-                Timer::new(Duration::from_secs(3), bevy::time::TimerMode::Once),
+            commands.spawn((
+                DomainName(
+                    domain.name.clone(),
+                    Timer::new(Duration::from_secs(3), bevy::time::TimerMode::Once),
+                ),
+                StateScoped(InRun),
             ));
         }
     }
@@ -119,6 +143,7 @@ fn new_domain_trigger(
 fn generate_floor(
     mut event: EventReader<TriggerNextFloorEvent>,
     mut writer: EventWriter<GenerateFloorEvent>,
+    mut run_writer: EventWriter<RunEndedEvent>,
     mut floor_resource: ResMut<FloorResource>,
     domain_manifest: Res<DomainManifest>,
 ) {
@@ -126,19 +151,17 @@ fn generate_floor(
         floor_resource.floor += 1;
 
         //TODO: Optimize this
-        let domain = domain_manifest
-            .domains
-            .values()
-            .find_map(|domain| {
-                if floor_resource.floor >= domain.floors.0
-                    && floor_resource.floor <= domain.floors.1
-                {
-                    Some(domain)
-                } else {
-                    None
-                }
-            })
-            .expect("No floor found");
+        let Some(domain) = domain_manifest.domains.values().find_map(|domain| {
+            if floor_resource.floor >= domain.floors.0 && floor_resource.floor <= domain.floors.1 {
+                Some(domain)
+            } else {
+                None
+            }
+        }) else {
+            //No more floors left, the run was won
+            run_writer.send(RunEndedEvent { victory: true });
+            return;
+        };
 
         floor_resource.domain = Some(domain.clone());
         writer.send(GenerateFloorEvent);
@@ -180,7 +203,10 @@ fn spawn_boss(
             if let Some(boss) = boss_manifest.get_by_name(domain.boss.clone()) {
                 floor.boss = Some(
                     commands
-                        .spawn(BossBundle::new(boss, e.portal_pos.extend(38.0)))
+                        .spawn((
+                            BossBundle::new(boss, e.portal_pos.extend(38.0)),
+                            StateScoped(InRun),
+                        ))
                         .insert(Idle)
                         .id(),
                 );
@@ -201,7 +227,7 @@ fn spawn_enemies(
                 return;
             }
 
-            let rand = Rng::new();
+            let rand = floor.rng();
             let spawnable_pos = &e.spawnable_pos;
 
             let spawnable_enemies = domain.enemies.clone();
@@ -224,12 +250,17 @@ fn spawn_enemies(
                         }
 
                         if let Some(enemy_data) = enemy_manifest.get_by_name(enemy.1.clone()) {
-                            floor.enemies.push(
-                                commands
-                                    .spawn(EnemyBundle::new(enemy_data, pos.1.extend(38.0)))
-                                    .insert(Idle)
-                                    .id(),
-                            );
+                            let mut ec = commands.spawn((
+                                EnemyBundle::new(enemy_data, pos.1.extend(38.0)),
+                                StateScoped(InRun),
+                            ));
+                            ec.insert(Idle);
+
+                            if enemy_data.name_plate {
+                                ec.insert(NamePlate);
+                            }
+
+                            floor.enemies.push(ec.id());
                         }
 
                         break;
@@ -244,15 +275,9 @@ fn enemy_killed(
     mut event: EventReader<EnemyKilledEvent>,
     mut level: ResMut<FloorResource>,
     mut portal_writer: EventWriter<FloorClearedEvent>,
-    mut commands: Commands,
 ) {
     for killed in event.read() {
-        level.enemies.retain(|e| *e != killed.0);
-
-        //        commands.entity(killed.0).despawn_recursive();
-        if let Some(ec) = commands.get_entity(killed.0) {
-            ec.despawn_recursive();
-        }
+        level.enemies.retain(|e| *e != killed.entity);
 
         if level.enemies.is_empty() {
             portal_writer.send(FloorClearedEvent);
@@ -3,8 +3,9 @@ use bevy_ecs_tilemap::TilemapPlugin;
 use bevy_persistent::prelude::*;
 use leafwing_input_manager::prelude::InputManagerPlugin;
 
+use crate::achievements::AchievementsPlugin;
 use crate::attack::{
-    attack_phase_system, attack_spawner, charge_phase_system, recover_phase_system,
+    attack_phase_system, attack_spawner, charge_phase_system, recover_phase_system, ParryEvent,
     SpawnEnemyAttack,
 };
 use crate::controller::combo_system;
@@ -12,26 +13,43 @@ use crate::effects::spawn_shadows;
 use crate::game_states::ingame::InGameSet::{Normal, Post};
 use crate::manifest::player::PlayerManifest;
 use crate::sorting::ysort;
-use crate::stats::{level_up, revenge_mode};
+use crate::stats::level_up;
 use crate::ui::boss::draw_domain_name;
-use crate::ui::player::{draw_hud, draw_revenge_bar, draw_xp_bar};
 use crate::{
     animation::AnimationPlugin,
+    area::AreaPlugin,
     attack::{lifetimes, projectile_break, tick_cooldown},
     collision::CollisionPlugin,
-    controller::{attack_ability, dash_ability, finish_dash, move_player},
+    controller::{
+        attack_ability, buffer_inputs, dash_ability, finish_dash, heavy_attack_ability,
+        move_player, parry_ability, parry_success, secondary_attack_ability, update_aim,
+    },
+    controls::Controls,
+    damage::DamagePlugin,
+    death::DeathPlugin,
     enemy::EnemyBehaviourPlugin,
+    feedback::FeedbackPlugin,
     floor::FloorPlugin,
-    helper::{helper_camera_controller, shake_system},
+    heal::HealPlugin,
+    health_bar::HealthBarPlugin,
+    helper::{shake_system, Shake},
+    hud::HudPlugin,
     map::{
         generation::{remake_map, setup_map},
         walkable::restrict_movement,
     },
+    meta::{apply_unlocks, MetaPlugin},
+    minimap::MinimapPlugin,
     movement::movement::{Follow, MovementPlugin},
     player::{PlayerActions, PlayerBundle},
-    statistics::{auto_save, statistics, Statistics},
-    stats::{death_system, drop_xp_system},
-    GameState,
+    projectile::ProjectilePlugin,
+    revenge::RevengePlugin,
+    settings::Settings,
+    statistics::{auto_save, kill_statistics, revenge_statistics, statistics},
+    stats::drop_xp_system,
+    status::StatusPlugin,
+    weapon::WeaponPlugin,
+    GameState, InRun,
 };
 
 pub struct InGamePlugin;
@@ -51,24 +69,42 @@ impl Plugin for InGamePlugin {
             .add_plugins(AnimationPlugin)
             .add_plugins(EnemyBehaviourPlugin)
             .add_plugins(FloorPlugin)
+            .add_plugins(HealPlugin)
+            .add_plugins(MetaPlugin)
+            .add_plugins(AchievementsPlugin)
+            .add_plugins(StatusPlugin)
+            .add_plugins(DamagePlugin)
+            .add_plugins(DeathPlugin)
+            .add_plugins(FeedbackPlugin)
+            .add_plugins(WeaponPlugin)
+            .add_plugins(RevengePlugin)
+            .add_plugins(ProjectilePlugin)
+            .add_plugins(AreaPlugin)
             .add_plugins(MovementPlugin)
+            .add_plugins(MinimapPlugin)
+            .add_plugins(HealthBarPlugin)
+            .add_plugins(HudPlugin)
             .add_event::<SpawnEnemyAttack>() //TODO: Add attack plugin
+            .add_event::<ParryEvent>()
             .add_systems(
                 Update,
-                (auto_save, statistics).run_if(in_state(GameState::InGame)),
+                (auto_save, statistics, kill_statistics, revenge_statistics)
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                OnEnter(GameState::InGame),
+                ((setup_game, apply_unlocks).chain(), setup_map),
             )
-            .add_systems(OnEnter(GameState::InGame), (setup_game, setup_map))
             //TODO: Check system ordering and optimize it
             .add_systems(
                 Update,
-                (draw_hud, draw_domain_name, draw_xp_bar, draw_revenge_bar)
+                draw_domain_name
                     .in_set(Normal)
                     .run_if(in_state(GameState::InGame)),
             )
             .add_systems(
                 Update,
                 (
-                    helper_camera_controller,
                     move_player,
                     dash_ability,
                     attack_ability,
@@ -81,7 +117,6 @@ impl Plugin for InGamePlugin {
                     projectile_break,
                     drop_xp_system,
                     level_up,
-                    revenge_mode,
                     charge_phase_system,
                     attack_phase_system,
                     recover_phase_system,
@@ -92,12 +127,19 @@ impl Plugin for InGamePlugin {
             .add_systems(
                 Update,
                 (
-                    spawn_shadows,
-                    restrict_movement,
-                    finish_dash,
-                    death_system,
-                    ysort,
+                    update_aim.before(attack_ability),
+                    buffer_inputs.before(attack_ability).before(dash_ability),
+                    secondary_attack_ability,
+                    heavy_attack_ability,
+                    parry_ability,
+                    parry_success,
                 )
+                    .in_set(Normal)
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                Update,
+                (spawn_shadows, restrict_movement, finish_dash, ysort)
                     .in_set(Post)
                     .after(Normal)
                     .run_if(in_state(GameState::InGame)),
@@ -105,25 +147,28 @@ impl Plugin for InGamePlugin {
     }
 }
 
-fn setup_game(mut commands: Commands, player_manifest: Res<PlayerManifest>) {
-    let dir = dirs::data_dir().unwrap().join("relentless_revenge");
-
-    commands.insert_resource(
-        Persistent::<Statistics>::builder()
-            .name("statistics")
-            .format(StorageFormat::Bincode)
-            .path(dir.join("statistic.bin"))
-            .default(Statistics::default())
-            .build()
-            .expect("Failed to create persistent statistics"),
-    );
-
+fn setup_game(
+    mut commands: Commands,
+    player_manifest: Res<PlayerManifest>,
+    controls: Res<Persistent<Controls>>,
+    settings: Res<Persistent<Settings>>,
+) {
     let player_data = &player_manifest.player_data;
-    let player_entity = commands.spawn(PlayerBundle::new(&player_data)).id();
+    let player_entity = commands
+        .spawn((
+            PlayerBundle::new(&player_data, &controls),
+            StateScoped(InRun),
+        ))
+        .id();
 
     //Add Camera after so we can give it the player entity
     let mut camera_bundle = Camera2dBundle::default();
-    camera_bundle.projection.scale = 0.25;
+    camera_bundle.projection.scale = settings.camera_scale();
 
-    commands.spawn((camera_bundle, Follow::new(player_entity, 2.5, true, 2.)));
+    commands.spawn((
+        camera_bundle,
+        Follow::new(player_entity, 2.5, true, 2.),
+        Shake::default(),
+        StateScoped(InRun),
+    ));
 }
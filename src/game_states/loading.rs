@@ -1,15 +1,22 @@
 use bevy::prelude::*;
 use bevy_egui::EguiPlugin;
+use bevy_persistent::prelude::*;
 use leafwing_manifest::asset_state::SimpleAssetState;
 
-use crate::{manifest::DataManifestPlugin, GameState};
+use crate::{
+    achievements::AchievementProgress, controls::Controls, manifest::DataManifestPlugin,
+    meta::MetaProgression, settings::Settings, statistics::Statistics, GameState,
+};
 
 pub struct LoadingPlugin;
 
 impl Plugin for LoadingPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((EguiPlugin, DataManifestPlugin {}))
-            .add_systems(OnEnter(GameState::Loading), setup_assets)
+            .add_systems(
+                OnEnter(GameState::Loading),
+                (setup_assets, setup_persistence),
+            )
             .add_systems(OnEnter(SimpleAssetState::Ready), finish_loading);
     }
 }
@@ -36,7 +43,65 @@ fn setup_assets(asset_server: Res<AssetServer>, mut commands: Commands) {
     });
 }
 
+fn setup_persistence(mut commands: Commands) {
+    let dir = dirs::data_dir().unwrap().join("relentless_revenge");
+
+    commands.insert_resource(
+        Persistent::<Statistics>::builder()
+            .name("statistics")
+            .format(StorageFormat::Bincode)
+            .path(dir.join("statistic.bin"))
+            .default(Statistics::default())
+            .build()
+            .expect("Failed to create persistent statistics"),
+    );
+
+    commands.insert_resource(
+        Persistent::<MetaProgression>::builder()
+            .name("meta progression")
+            .format(StorageFormat::Bincode)
+            .path(dir.join("meta.bin"))
+            .default(MetaProgression::default())
+            .build()
+            .expect("Failed to create persistent meta progression"),
+    );
+
+    commands.insert_resource(
+        Persistent::<AchievementProgress>::builder()
+            .name("achievements")
+            .format(StorageFormat::Bincode)
+            .path(dir.join("achievements.bin"))
+            .default(AchievementProgress::default())
+            .build()
+            .expect("Failed to create persistent achievements"),
+    );
+
+    let mut controls = Persistent::<Controls>::builder()
+        .name("controls")
+        .format(StorageFormat::Bincode)
+        .path(dir.join("controls.bin"))
+        .default(Controls::default())
+        .build()
+        .expect("Failed to create persistent controls");
+
+    if controls.add_missing() {
+        let _ = controls.persist();
+    }
+
+    commands.insert_resource(controls);
+
+    commands.insert_resource(
+        Persistent::<Settings>::builder()
+            .name("settings")
+            .format(StorageFormat::Bincode)
+            .path(dir.join("settings.bin"))
+            .default(Settings::default())
+            .build()
+            .expect("Failed to create persistent settings"),
+    );
+}
+
 fn finish_loading(mut next_state: ResMut<NextState<GameState>>) {
     //TODO: Check if our own assets have loaded aswell
-    next_state.set(GameState::InGame);
+    next_state.set(GameState::MainMenu);
 }
@@ -1,22 +1,158 @@
 use bevy::{
-    input::ButtonInput,
-    prelude::{App, KeyCode, NextState, OnEnter, Plugin, Res, ResMut},
+    input::{gamepad::GamepadButton, ButtonInput},
+    prelude::{
+        in_state, resource_equals, App, Camera2dBundle, Commands, Condition, GamepadButtonType,
+        IntoSystemConfigs, KeyCode, NextState, OnEnter, Plugin, Res, ResMut, Resource, StateScoped,
+        Update,
+    },
+};
+use bevy_egui::{
+    egui::{self, RichText},
+    EguiContexts,
 };
 
-use crate::GameState;
+use crate::{
+    ui::{
+        achievements::draw_achievements_menu,
+        controls::{draw_controls_menu, Rebinding},
+        settings::draw_settings_menu,
+        unlocks::draw_unlocks_menu,
+    },
+    GameState,
+};
 
 pub struct MainMenuPlugin;
 
 impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::MainMenu), (setup_menu, skip_menu));
+        app.init_resource::<MenuPage>()
+            .init_resource::<Rebinding>()
+            .add_systems(OnEnter(GameState::MainMenu), setup_menu)
+            .add_systems(
+                Update,
+                (skip_menu, draw_main_menu).run_if(
+                    in_state(GameState::MainMenu).and_then(resource_equals(MenuPage::Main)),
+                ),
+            )
+            .add_systems(
+                Update,
+                draw_unlocks_menu.run_if(
+                    in_state(GameState::MainMenu).and_then(resource_equals(MenuPage::Unlocks)),
+                ),
+            )
+            .add_systems(
+                Update,
+                draw_achievements_menu.run_if(
+                    in_state(GameState::MainMenu).and_then(resource_equals(MenuPage::Achievements)),
+                ),
+            )
+            .add_systems(
+                Update,
+                draw_controls_menu.run_if(
+                    in_state(GameState::MainMenu).and_then(resource_equals(MenuPage::Controls)),
+                ),
+            )
+            .add_systems(
+                Update,
+                draw_settings_menu.run_if(
+                    in_state(GameState::MainMenu).and_then(resource_equals(MenuPage::Settings)),
+                ),
+            )
+            .add_systems(
+                Update,
+                gamepad_back
+                    .before(draw_controls_menu)
+                    .run_if(in_state(GameState::MainMenu)),
+            );
     }
 }
 
-fn setup_menu() {}
+//Current screen shown in the main menu
+#[derive(Resource, Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MenuPage {
+    #[default]
+    Main,
+    Unlocks,
+    Achievements,
+    Controls,
+    Settings,
+}
+
+fn setup_menu(mut commands: Commands, mut page: ResMut<MenuPage>) {
+    *page = MenuPage::Main;
+    commands.spawn((Camera2dBundle::default(), StateScoped(GameState::MainMenu)));
+}
 
-fn skip_menu(keys: Res<ButtonInput<KeyCode>>, mut state: ResMut<NextState<GameState>>) {
-    if keys.any_just_pressed([KeyCode::Space, KeyCode::Backslash]) {
+fn skip_menu(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    if keys.any_just_pressed([KeyCode::Space, KeyCode::Backslash])
+        || gamepad_buttons
+            .get_just_pressed()
+            .any(|button| button.button_type == GamepadButtonType::Start)
+    {
         state.set(GameState::InGame);
     }
 }
+
+//B goes back to the main page, unless it is being bound to an action
+fn gamepad_back(
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    rebinding: Res<Rebinding>,
+    mut page: ResMut<MenuPage>,
+) {
+    if *page == MenuPage::Main || rebinding.0.is_some() {
+        return;
+    }
+
+    if gamepad_buttons
+        .get_just_pressed()
+        .any(|button| button.button_type == GamepadButtonType::East)
+    {
+        *page = MenuPage::Main;
+    }
+}
+
+fn draw_main_menu(
+    mut contexts: EguiContexts,
+    mut state: ResMut<NextState<GameState>>,
+    mut page: ResMut<MenuPage>,
+) {
+    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+        ui.vertical_centered(|ui| {
+            ui.add_space(ui.available_height() / 4.);
+            ui.heading(RichText::new("Relentless Revenge").size(48.).strong());
+            ui.add_space(40.);
+
+            if ui.button(RichText::new("Play").size(26.)).clicked() {
+                state.set(GameState::InGame);
+            }
+
+            ui.add_space(10.);
+
+            if ui.button(RichText::new("Unlocks").size(26.)).clicked() {
+                *page = MenuPage::Unlocks;
+            }
+
+            ui.add_space(10.);
+
+            if ui.button(RichText::new("Achievements").size(26.)).clicked() {
+                *page = MenuPage::Achievements;
+            }
+
+            ui.add_space(10.);
+
+            if ui.button(RichText::new("Controls").size(26.)).clicked() {
+                *page = MenuPage::Controls;
+            }
+
+            ui.add_space(10.);
+
+            if ui.button(RichText::new("Settings").size(26.)).clicked() {
+                *page = MenuPage::Settings;
+            }
+        });
+    });
+}
@@ -1,3 +1,4 @@
 pub mod ingame;
 pub mod loading;
 pub mod menu;
+pub mod summary;
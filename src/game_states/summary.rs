@@ -0,0 +1,197 @@
+use bevy::{input::gamepad::GamepadButton, prelude::*};
+use bevy_egui::{
+    egui::{self, Align2, RichText},
+    EguiContexts,
+};
+use bevy_persistent::prelude::*;
+
+use crate::{
+    floor::{FloorResource, RunEndedEvent},
+    meta::MetaProgression,
+    player::Player,
+    statistics::Statistics,
+    stats::Level,
+    weapon::Weapons,
+    GameState,
+};
+
+pub struct RunSummaryPlugin;
+
+impl Plugin for RunSummaryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::InGame), start_run)
+            .add_systems(Update, end_run.run_if(in_state(GameState::InGame)))
+            .add_systems(
+                Update,
+                draw_run_summary.run_if(in_state(GameState::RunSummary)),
+            )
+            .add_systems(OnExit(GameState::RunSummary), cleanup_run);
+    }
+}
+
+//Statistics when the run started, the summary shows the difference
+#[derive(Resource)]
+struct RunStart {
+    statistics: Statistics,
+    started: f32, // Elapsed seconds when the run started
+    relics: Vec<String>,
+}
+
+#[derive(Resource)]
+pub struct RunSummary {
+    pub victory: bool,
+    pub floor: u32,
+    pub domain: Option<String>,
+    pub time: f32,
+    pub kills: u32,
+    pub damage_dealt: u32,
+    pub damage_taken: u32,
+    pub level: i32,
+    pub weapons: Vec<String>,
+    pub relics: Vec<String>, // Unlocks bought before the run, with their level
+}
+
+fn start_run(
+    mut commands: Commands,
+    mut statistics: ResMut<Persistent<Statistics>>,
+    meta: Res<Persistent<MetaProgression>>,
+    time: Res<Time>,
+) {
+    statistics.game_count += 1;
+
+    let mut relics = meta
+        .unlocks
+        .iter()
+        .filter(|(_, level)| **level > 0)
+        .map(|(name, level)| match level {
+            1 => name.clone(),
+            _ => format!("{} {}", name, level),
+        })
+        .collect::<Vec<_>>();
+    relics.sort();
+
+    commands.insert_resource(RunStart {
+        statistics: statistics.get().clone(),
+        started: time.elapsed_seconds(),
+        relics,
+    });
+}
+
+fn end_run(
+    mut events: EventReader<RunEndedEvent>,
+    mut commands: Commands,
+    mut state: ResMut<NextState<GameState>>,
+    statistics: Res<Persistent<Statistics>>,
+    start: Res<RunStart>,
+    floor: Res<FloorResource>,
+    player_query: Query<(&Level, &Weapons), With<Player>>,
+    time: Res<Time>,
+) {
+    let Some(event) = events.read().last() else {
+        return;
+    };
+
+    let (level, weapons) = player_query
+        .get_single()
+        .map(|(level, weapons)| {
+            (
+                level.level,
+                weapons.slots.iter().flatten().cloned().collect::<Vec<_>>(),
+            )
+        })
+        .unwrap_or_default();
+
+    commands.insert_resource(RunSummary {
+        victory: event.victory,
+        floor: floor.floor,
+        domain: floor.domain.as_ref().map(|domain| domain.name.clone()),
+        time: time.elapsed_seconds() - start.started,
+        kills: statistics.kills - start.statistics.kills,
+        damage_dealt: statistics.damage_dealt - start.statistics.damage_dealt,
+        damage_taken: statistics.damage_taken - start.statistics.damage_taken,
+        level,
+        weapons,
+        relics: start.relics.clone(),
+    });
+
+    let _ = statistics.persist();
+    state.set(GameState::RunSummary);
+}
+
+fn draw_run_summary(
+    mut contexts: EguiContexts,
+    mut state: ResMut<NextState<GameState>>,
+    summary: Res<RunSummary>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+) {
+    let title = if summary.victory {
+        "Victory"
+    } else {
+        "You Died"
+    };
+
+    let minutes = (summary.time / 60.) as u32;
+    let seconds = (summary.time % 60.) as u32;
+
+    let rows = [
+        ("Floor", summary.floor.to_string()),
+        (
+            "Domain",
+            summary.domain.clone().unwrap_or_else(|| "-".to_string()),
+        ),
+        ("Time", format!("{}:{:02}", minutes, seconds)),
+        ("Kills", summary.kills.to_string()),
+        ("Damage dealt", summary.damage_dealt.to_string()),
+        ("Damage taken", summary.damage_taken.to_string()),
+        ("Level", summary.level.to_string()),
+        ("Weapons", summary.weapons.join(", ")),
+        ("Relics", summary.relics.join(", ")),
+    ];
+
+    egui::Window::new("Run Summary")
+        .title_bar(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.vertical_centered(|ui| {
+                ui.heading(RichText::new(title).size(36.).strong());
+            });
+
+            ui.separator();
+
+            egui::Grid::new("run_summary")
+                .num_columns(2)
+                .spacing([40., 6.])
+                .show(ui, |ui| {
+                    for (label, value) in rows {
+                        ui.label(RichText::new(label).size(18.));
+                        ui.label(RichText::new(value).size(18.).strong());
+                        ui.end_row();
+                    }
+                });
+
+            ui.add_space(10.);
+
+            ui.vertical_centered(|ui| {
+                if ui.button(RichText::new("Main Menu").size(22.)).clicked()
+                    || keys.just_pressed(KeyCode::Enter)
+                    || gamepad_buttons.get_just_pressed().any(|button| {
+                        matches!(
+                            button.button_type,
+                            GamepadButtonType::South | GamepadButtonType::Start
+                        )
+                    })
+                {
+                    state.set(GameState::MainMenu);
+                }
+            });
+        });
+}
+
+//The run's entities are scoped to InRun, only its resources are left to reset
+fn cleanup_run(mut commands: Commands, mut floor: ResMut<FloorResource>) {
+    *floor = FloorResource::default();
+    commands.remove_resource::<RunSummary>();
+    commands.remove_resource::<RunStart>();
+}
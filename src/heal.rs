@@ -0,0 +1,266 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_persistent::prelude::*;
+use bevy_rapier2d::{prelude::*, rapier::prelude::CollisionEventFlags};
+use leafwing_manifest::identifier::Id;
+use turborand::{rng::Rng, TurboRand};
+
+use crate::{
+    collision::BodyLayers,
+    effects::floating_text,
+    floor::{EnemyKilledEvent, FloorResource, SpawnFloorEntitiesEvent},
+    game_states::loading::GameAssets,
+    manifest::enemy::EnemyManifest,
+    player::Player,
+    statistics::Statistics,
+    stats::Health,
+    GameState, InRun,
+};
+
+pub struct HealPlugin;
+
+impl Plugin for HealPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<HealEvent>().add_systems(
+            Update,
+            (
+                heal_system,
+                drop_health_orb_system,
+                health_orb_pickup,
+                spawn_fountains,
+                fountain_system,
+            )
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+//Every source of healing should go through this event
+#[derive(Event)]
+pub struct HealEvent {
+    pub entity: Entity,
+    pub amount: u32,
+}
+
+//Percentage of the damage dealt that is healed back
+#[derive(Component)]
+pub struct Lifesteal(pub f32);
+
+#[derive(Component)]
+pub struct HealthOrb(pub u32);
+
+#[derive(Component)]
+pub struct Fountain {
+    pub amount: u32,
+    pub used: bool,
+}
+
+pub fn heal_system(
+    mut events: EventReader<HealEvent>,
+    mut query: Query<(&mut Health, &Transform, Option<&Player>)>,
+    mut statistics: ResMut<Persistent<Statistics>>,
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+) {
+    for event in events.read() {
+        if let Ok((mut health, transform, player)) = query.get_mut(event.entity) {
+            let healed = health.heal(event.amount);
+
+            if healed == 0 {
+                continue;
+            }
+
+            if player.is_some() {
+                statistics.healing += healed;
+            }
+
+            commands.spawn(floating_text(
+                game_assets.font.clone(),
+                transform.translation.xy(),
+                format!("+{}", healed),
+                Color::srgb(0.2, 0.9, 0.3),
+                12.,
+            ));
+        }
+    }
+}
+
+#[derive(Bundle)]
+pub struct HealthOrbBundle {
+    pub orb: HealthOrb,
+    pub sprite_bundle: SpriteBundle,
+    pub collider: Collider,
+    pub collision_events: ActiveEvents,
+    pub collision_types: ActiveCollisionTypes,
+    pub collision_groups: CollisionGroups,
+}
+
+impl HealthOrbBundle {
+    pub fn new(location: Vec2, amount: u32, texture: Handle<Image>) -> Self {
+        Self {
+            orb: HealthOrb(amount),
+            sprite_bundle: SpriteBundle {
+                //TODO: Add a proper health orb sprite
+                sprite: Sprite {
+                    color: Color::srgb(0.2, 0.9, 0.3),
+                    ..default()
+                },
+                texture,
+                transform: Transform::from_translation(location.extend(3.)),
+                ..default()
+            },
+            collider: Collider::ball(4.),
+            collision_events: ActiveEvents::COLLISION_EVENTS,
+            collision_types: ActiveCollisionTypes::all(),
+            collision_groups: CollisionGroups::new(BodyLayers::PICKUP_LAYER, BodyLayers::PLAYER),
+        }
+    }
+}
+
+pub fn drop_health_orb_system(
+    mut commands: Commands,
+    mut enemy_kill_reader: EventReader<EnemyKilledEvent>,
+    enemy_manifest: Res<EnemyManifest>,
+    game_assets: Res<GameAssets>,
+) {
+    let rand = Rng::new();
+
+    for event in enemy_kill_reader.read() {
+        let Some(enemy_data) = enemy_manifest
+            .enemies
+            .get(&Id::from_name(event.name.as_str()))
+        else {
+            continue;
+        };
+
+        if let Some((chance, amount)) = enemy_data.health_orb {
+            if rand.f32() <= chance {
+                //Offset it a bit so it doesn't overlap the xp drop
+                let location = event.position + Vec2::new(6., 0.);
+                commands.spawn((
+                    HealthOrbBundle::new(location, amount, game_assets.xp_texture.clone()),
+                    StateScoped(InRun),
+                ));
+            }
+        }
+    }
+}
+
+pub fn health_orb_pickup(
+    mut commands: Commands,
+    mut events: EventReader<CollisionEvent>,
+    orb_query: Query<&HealthOrb>,
+    player_query: Query<Entity, With<Player>>,
+    mut heal_writer: EventWriter<HealEvent>,
+) {
+    events.read().for_each(|e| {
+        let CollisionEvent::Started(e1, e2, flags) = e else {
+            return;
+        };
+
+        if *flags == CollisionEventFlags::REMOVED {
+            return;
+        }
+
+        if let Some((orb_entity, player_entity)) = match (
+            orb_query.contains(*e1) && player_query.contains(*e2),
+            orb_query.contains(*e2) && player_query.contains(*e1),
+        ) {
+            (true, false) => Some((*e1, *e2)),
+            (false, true) => Some((*e2, *e1)),
+            _ => None,
+        } {
+            let orb = orb_query.get(orb_entity).unwrap();
+
+            heal_writer.send(HealEvent {
+                entity: player_entity,
+                amount: orb.0,
+            });
+            commands.entity(orb_entity).despawn_recursive();
+        }
+    });
+}
+
+pub fn spawn_fountains(
+    mut commands: Commands,
+    mut event: EventReader<SpawnFloorEntitiesEvent>,
+    fountains: Query<Entity, With<Fountain>>,
+    floor: Res<FloorResource>,
+) {
+    for e in event.read() {
+        //Fountains only last for the floor they were spawned on
+        for entity in fountains.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+
+        let Some((chance, amount)) = floor.domain.as_ref().and_then(|d| d.fountains) else {
+            continue;
+        };
+
+        let rand = Rng::new();
+
+        for pos in e.room_centers.iter() {
+            if rand.f32() > chance {
+                continue;
+            }
+
+            commands.spawn((
+                Fountain {
+                    amount,
+                    used: false,
+                },
+                SpriteBundle {
+                    //TODO: Add a proper fountain sprite
+                    sprite: Sprite {
+                        color: Color::srgb(0.3, 0.8, 0.9),
+                        custom_size: Some(Vec2::new(16., 16.)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(pos.extend(3.)),
+                    ..default()
+                },
+                Collider::ball(10.),
+                Sensor,
+                ActiveEvents::COLLISION_EVENTS,
+                ActiveCollisionTypes::all(),
+                CollisionGroups::new(BodyLayers::PICKUP_LAYER, BodyLayers::PLAYER),
+                StateScoped(InRun),
+            ));
+        }
+    }
+}
+
+pub fn fountain_system(
+    mut events: EventReader<CollisionEvent>,
+    mut fountain_query: Query<(&mut Fountain, &mut Sprite)>,
+    player_query: Query<Entity, With<Player>>,
+    mut heal_writer: EventWriter<HealEvent>,
+) {
+    for e in events.read() {
+        let CollisionEvent::Started(e1, e2, _) = e else {
+            continue;
+        };
+
+        let (fountain_entity, player_entity) = match (
+            fountain_query.contains(*e1) && player_query.contains(*e2),
+            fountain_query.contains(*e2) && player_query.contains(*e1),
+        ) {
+            (true, false) => (*e1, *e2),
+            (false, true) => (*e2, *e1),
+            _ => continue,
+        };
+
+        if let Ok((mut fountain, mut sprite)) = fountain_query.get_mut(fountain_entity) {
+            if fountain.used {
+                continue;
+            }
+
+            fountain.used = true;
+            sprite.color = Color::srgb(0.4, 0.4, 0.4);
+
+            heal_writer.send(HealEvent {
+                entity: player_entity,
+                amount: fountain.amount,
+            });
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use bevy::{prelude::*, sprite::Anchor};
+use bevy_rapier2d::prelude::Collider;
+
+use crate::{
+    boss::Boss,
+    death::Dying,
+    enemy::{Enemy, NamePlate},
+    game_states::loading::GameAssets,
+    stats::Health,
+    ui::enemy::draw_straggler_arrows,
+    GameState,
+};
+
+pub struct HealthBarPlugin;
+
+impl Plugin for HealthBarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                spawn_health_bars,
+                update_health_bars,
+                spawn_name_plates,
+                remove_overlays,
+                draw_straggler_arrows,
+            )
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+const BAR_WIDTH: f32 = 20.;
+const BAR_HEIGHT: f32 = 2.5;
+const BAR_MARGIN: f32 = 6.; // Space between the hitbox and the bar
+const VISIBLE_TIME: f32 = 3.; // Seconds at full opacity after the last hit
+const FADE_TIME: f32 = 1.;
+
+//Bars and name plates drawn above enemies, children of the enemy they belong to
+#[derive(Component)]
+struct EnemyOverlay;
+
+#[derive(Component)]
+struct HealthBar {
+    fill: Entity,
+    background: Entity,
+    timer: Timer,
+}
+
+//Enemy that already has a HealthBar child
+#[derive(Component)]
+struct HasHealthBar(Entity);
+
+//Height above the enemy origin, in its local space so the parent scale is undone
+fn overlay_offset(collider: Option<&Collider>, scale: Vec3, extra: f32) -> Vec3 {
+    let half_height = collider
+        .and_then(|collider| collider.as_cuboid())
+        .map_or(16., |cuboid| cuboid.half_extents().y);
+
+    Vec3::new(0., half_height + extra / scale.y, 10.)
+}
+
+fn spawn_health_bars(
+    mut commands: Commands,
+    query: Query<
+        (
+            Entity,
+            &Health,
+            &Transform,
+            Option<&Collider>,
+            Option<&HasHealthBar>,
+        ),
+        (
+            Or<(With<Enemy>, With<Boss>)>,
+            Changed<Health>,
+            Without<Dying>,
+        ),
+    >,
+    mut bars: Query<&mut HealthBar>,
+) {
+    for (entity, health, transform, collider, has_bar) in query.iter() {
+        if health.current >= health.max {
+            continue;
+        }
+
+        if let Some(mut bar) = has_bar.and_then(|has_bar| bars.get_mut(has_bar.0).ok()) {
+            bar.timer.reset();
+            continue;
+        }
+
+        let background = commands
+            .spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgba(0.1, 0.05, 0.05, 0.8),
+                    custom_size: Some(Vec2::new(BAR_WIDTH + 1., BAR_HEIGHT + 1.)),
+                    ..default()
+                },
+                ..default()
+            })
+            .id();
+
+        let fill = commands
+            .spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(0.85, 0.15, 0.15),
+                    custom_size: Some(Vec2::new(BAR_WIDTH, BAR_HEIGHT)),
+                    anchor: Anchor::CenterLeft,
+                    ..default()
+                },
+                transform: Transform::from_xyz(-BAR_WIDTH / 2., 0., 0.1),
+                ..default()
+            })
+            .id();
+
+        let bar = commands
+            .spawn((
+                SpatialBundle::from_transform(
+                    Transform::from_translation(overlay_offset(
+                        collider,
+                        transform.scale,
+                        BAR_MARGIN,
+                    ))
+                    .with_scale(transform.scale.recip()),
+                ),
+                HealthBar {
+                    fill,
+                    background,
+                    timer: Timer::from_seconds(VISIBLE_TIME + FADE_TIME, TimerMode::Once),
+                },
+                EnemyOverlay,
+            ))
+            .push_children(&[background, fill])
+            .id();
+
+        commands
+            .entity(entity)
+            .add_child(bar)
+            .insert(HasHealthBar(bar));
+    }
+}
+
+fn update_health_bars(
+    mut bars: Query<(&mut HealthBar, &Parent)>,
+    health_query: Query<&Health>,
+    mut sprites: Query<&mut Sprite>,
+    time: Res<Time>,
+) {
+    for (mut bar, parent) in bars.iter_mut() {
+        let Ok(health) = health_query.get(parent.get()) else {
+            continue;
+        };
+
+        bar.timer.tick(time.delta());
+
+        //Fully visible until the last second, then fades out
+        let remaining = bar.timer.duration().as_secs_f32() - bar.timer.elapsed_secs();
+        let alpha = (remaining / FADE_TIME).min(1.);
+
+        if let Ok(mut fill) = sprites.get_mut(bar.fill) {
+            let ratio = health.current as f32 / health.max as f32;
+            fill.custom_size = Some(Vec2::new(BAR_WIDTH * ratio, BAR_HEIGHT));
+            fill.color.set_alpha(alpha);
+        }
+
+        if let Ok(mut background) = sprites.get_mut(bar.background) {
+            background.color.set_alpha(alpha * 0.8);
+        }
+    }
+}
+
+//Bosses and enemies with a name plate always show their name
+fn spawn_name_plates(
+    mut commands: Commands,
+    query: Query<
+        (
+            Entity,
+            &Transform,
+            Option<&Collider>,
+            Option<&Boss>,
+            Option<&Enemy>,
+        ),
+        Or<(Added<NamePlate>, Added<Boss>)>,
+    >,
+    game_assets: Res<GameAssets>,
+) {
+    for (entity, transform, collider, boss, enemy) in query.iter() {
+        let (name, color) = match (boss, enemy) {
+            (Some(boss), _) => (boss.0.clone(), Color::srgb(1., 0.85, 0.3)),
+            (None, Some(enemy)) => (enemy.0.clone(), Color::srgb(1., 0.55, 0.2)),
+            _ => continue,
+        };
+
+        let plate = commands
+            .spawn((
+                Text2dBundle {
+                    text: Text::from_section(
+                        name,
+                        TextStyle {
+                            font: game_assets.font.clone(),
+                            font_size: 10.,
+                            color,
+                        },
+                    )
+                    .with_justify(JustifyText::Center),
+                    transform: Transform::from_translation(overlay_offset(
+                        collider,
+                        transform.scale,
+                        BAR_MARGIN + 8.,
+                    ))
+                    .with_scale(transform.scale.recip()),
+                    ..default()
+                },
+                EnemyOverlay,
+            ))
+            .id();
+
+        commands.entity(entity).add_child(plate);
+    }
+}
+
+//Dying enemies fade on their own, their bars and names go away right away
+fn remove_overlays(
+    mut commands: Commands,
+    query: Query<&Children, Added<Dying>>,
+    overlays: Query<(), With<EnemyOverlay>>,
+) {
+    for children in query.iter() {
+        for child in children.iter() {
+            if overlays.contains(*child) {
+                commands.entity(*child).despawn_recursive();
+            }
+        }
+    }
+}
@@ -1,24 +1,28 @@
-use crate::movement::movement::Follow;
-use bevy::input::ButtonInput;
+use crate::settings::Settings;
 use bevy::math::Vec3Swizzles;
-use bevy::prelude::{
-    Commands, Component, Entity, KeyCode, Query, Res, Resource, Time, Transform, With,
-};
-use bevy::render::camera::OrthographicProjection;
+use bevy::prelude::{Component, Quat, Query, Res, Time, Transform, Vec2};
+use bevy_persistent::prelude::Persistent;
 use noisy_bevy::fbm_simplex_2d_seeded;
 
 #[derive(Component)]
 pub struct Parallax;
 
-#[derive(Component)]
+//Trauma based screen shake, the offset grows with the square of the trauma
+#[derive(Component, Default)]
 pub struct Shake {
-    pub strength: f32,
-    pub duration: f32,
+    pub trauma: f32,
+    offset: Vec2, // Added last frame, taken back out before the next one so the camera doesn't drift
+}
+
+impl Shake {
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.);
+    }
 }
 
 pub fn shake_system(
-    mut commands: Commands,
-    mut query: Query<(&mut Transform, &mut Shake, Entity)>,
+    mut query: Query<(&mut Transform, &mut Shake)>,
+    settings: Res<Persistent<Settings>>,
     time: Res<Time>,
 ) {
     //Move this a good spot
@@ -26,97 +30,50 @@ pub fn shake_system(
     const OCTAVES: usize = 4;
     const LACUNARITY: f32 = 2.;
     const GAIN: f32 = 1.75;
+    const MAX_OFFSET: f32 = 4.;
+    const MAX_ANGLE: f32 = 0.05;
+    const DECAY: f32 = 1.5;
 
-    for (mut trans, mut shake, entity) in query.iter_mut() {
-        if shake.duration > 0. {
-            //let rand = Rng::new();
+    for (mut trans, mut shake) in query.iter_mut() {
+        trans.translation -= shake.offset.extend(0.);
+        shake.offset = Vec2::ZERO;
 
-            let pos = trans.translation.xy();
-            let x_offset = fbm_simplex_2d_seeded(
-                pos * FREQUENCY_SCALE,
-                OCTAVES,
-                LACUNARITY,
-                GAIN,
-                time.delta_seconds(),
-            ) * shake.strength;
+        if shake.trauma <= 0. {
+            trans.rotation = Quat::IDENTITY;
+            continue;
+        }
 
-            let y_offset = fbm_simplex_2d_seeded(
-                pos * FREQUENCY_SCALE,
-                OCTAVES,
-                LACUNARITY,
-                GAIN,
-                time.delta_seconds() + 100.0,
-            ) * shake.strength;
+        let strength = shake.trauma.powi(2) * settings.shake;
+        let seed = time.elapsed_seconds() * 10.;
+        let pos = trans.translation.xy();
 
-            let angle_offset = fbm_simplex_2d_seeded(
-                pos * FREQUENCY_SCALE,
-                OCTAVES,
-                LACUNARITY,
-                GAIN / 2.,
-                time.delta_seconds() + 50.,
-            ) * shake.strength
-                / 200.;
+        let x_offset =
+            fbm_simplex_2d_seeded(pos * FREQUENCY_SCALE, OCTAVES, LACUNARITY, GAIN, seed)
+                * strength
+                * MAX_OFFSET;
 
-            trans.translation.x += x_offset * time.delta_seconds();
-            trans.translation.y += y_offset * time.delta_seconds();
+        let y_offset = fbm_simplex_2d_seeded(
+            pos * FREQUENCY_SCALE,
+            OCTAVES,
+            LACUNARITY,
+            GAIN,
+            seed + 100.0,
+        ) * strength
+            * MAX_OFFSET;
 
-            trans.rotate_z(angle_offset * time.delta_seconds());
+        let angle_offset = fbm_simplex_2d_seeded(
+            pos * FREQUENCY_SCALE,
+            OCTAVES,
+            LACUNARITY,
+            GAIN / 2.,
+            seed + 50.,
+        ) * strength
+            * MAX_ANGLE;
 
-            shake.duration -= time.delta_seconds();
-        } else {
-            commands.entity(entity).remove::<Shake>();
-        }
-    }
-}
+        shake.offset = Vec2::new(x_offset, y_offset);
+        trans.translation += shake.offset.extend(0.);
+        trans.rotation = Quat::from_rotation_z(angle_offset);
 
-//Helper camera controller
-pub fn helper_camera_controller(
-    mut query: Query<(&mut OrthographicProjection, &mut Transform), With<Follow>>,
-    keys: Res<ButtonInput<KeyCode>>,
-    time: Res<Time>,
-) {
-    if let Ok((mut projection, mut transform)) = query.get_single_mut() {
-        if keys.pressed(KeyCode::ArrowUp) {
-            transform.translation.y += 150.0 * time.delta_seconds();
-        }
-        if keys.pressed(KeyCode::ArrowLeft) {
-            transform.translation.x -= 150.0 * time.delta_seconds();
-        }
-        if keys.pressed(KeyCode::ArrowDown) {
-            transform.translation.y -= 150.0 * time.delta_seconds();
-        }
-        if keys.pressed(KeyCode::ArrowRight) {
-            transform.translation.x += 150.0 * time.delta_seconds();
-        }
-
-        if keys.pressed(KeyCode::KeyZ) {
-            projection.scale -= 1. * time.delta_seconds();
-        }
-        if keys.pressed(KeyCode::KeyX) {
-            projection.scale += 1. * time.delta_seconds();
-        }
-    }
-}
-
-#[derive(Resource)]
-pub struct KeyMaps {
-    pub walk_up: KeyCode,
-    pub walk_left: KeyCode,
-    pub walk_down: KeyCode,
-    pub walk_right: KeyCode,
-    pub attack: KeyCode,
-    pub dash: KeyCode,
-}
-
-impl Default for KeyMaps {
-    fn default() -> Self {
-        KeyMaps {
-            walk_up: KeyCode::KeyW,
-            walk_left: KeyCode::KeyA,
-            walk_down: KeyCode::KeyS,
-            walk_right: KeyCode::KeyD,
-            attack: KeyCode::KeyJ,
-            dash: KeyCode::Space,
-        }
+        shake.trauma = (shake.trauma - DECAY * time.delta_seconds()).max(0.);
     }
 }
@@ -0,0 +1,162 @@
+use bevy::prelude::*;
+use bevy_egui::{egui::TextureId, EguiContexts};
+
+use crate::{
+    feedback::HitEvent,
+    player::Player,
+    stats::{Health, Level, XP},
+    ui::hud::{draw_action_prompts, draw_combo_counter, draw_cooldowns, draw_hud, draw_top_bars},
+    GameState, Progression,
+};
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::InGame), setup_hud)
+            .add_systems(
+                Update,
+                (
+                    (animate_bars, count_hits),
+                    (
+                        draw_hud,
+                        draw_top_bars,
+                        draw_cooldowns,
+                        draw_combo_counter,
+                        draw_action_prompts,
+                    ),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+//Window height the HUD sizes are made for
+const REFERENCE_HEIGHT: f32 = 720.;
+
+const TRAIL_DELAY: f32 = 0.4; // Seconds before the damage trail starts catching up
+const TRAIL_SPEED: f32 = 0.6; // Part of the bar the trail shrinks per second
+const XP_FILL_SPEED: f32 = 1.5;
+pub const HIT_WINDOW: f32 = 2.; // Seconds without landing a hit before the counter resets
+
+//Textures registered with egui once instead of every frame
+#[derive(Resource)]
+pub struct HudTextures {
+    pub health_fill: TextureId,
+    pub health_border: TextureId,
+    _handles: [Handle<Image>; 2], // Keeps the images loaded
+}
+
+//Values the HUD shows, the bars move towards the real ones over time
+#[derive(Resource)]
+pub struct HudState {
+    pub health_trail: f32,
+    trail_delay: f32,
+    pub xp_fill: f32,
+    level: i32,
+    pub hits: u32,
+    pub hits_timer: f32,
+}
+
+impl Default for HudState {
+    fn default() -> Self {
+        Self {
+            health_trail: 1.,
+            trail_delay: 0.,
+            xp_fill: 0.,
+            level: 1,
+            hits: 0,
+            hits_timer: 0.,
+        }
+    }
+}
+
+//Multiplier for HUD sizes so it keeps its proportions on bigger windows
+pub fn ui_scale(window: &Window) -> f32 {
+    (window.height() / REFERENCE_HEIGHT).clamp(0.75, 2.)
+}
+
+fn setup_hud(mut commands: Commands, mut contexts: EguiContexts, asset_server: Res<AssetServer>) {
+    let fill = asset_server.load("health_bar_fill.png");
+    let border = asset_server.load("health_bar_border.png");
+
+    commands.insert_resource(HudTextures {
+        health_fill: contexts.add_image(fill.clone_weak()),
+        health_border: contexts.add_image(border.clone_weak()),
+        _handles: [fill, border],
+    });
+    commands.insert_resource(HudState::default());
+}
+
+//Progress from the current level to the next one
+fn xp_ratio(xp: &XP, progression: &Progression, level: &Level) -> f32 {
+    let start_xp = match level.level {
+        1 => 0,
+        _ => progression.xp_to_level_up(level.level - 1),
+    };
+    let next_xp = progression.xp_to_level_up(level.level);
+
+    (xp.amount.saturating_sub(start_xp) as f32 / next_xp.saturating_sub(start_xp).max(1) as f32)
+        .clamp(0., 1.)
+}
+
+fn animate_bars(
+    mut hud: ResMut<HudState>,
+    query: Query<(&Health, &XP, &Progression, &Level), With<Player>>,
+    time: Res<Time>,
+) {
+    let Ok((health, xp, progression, level)) = query.get_single() else {
+        return;
+    };
+
+    let delta = time.delta_seconds();
+    let health_ratio = health.current as f32 / health.max as f32;
+
+    //The trail stays where the health was for a moment and then shrinks down to it
+    if health_ratio >= hud.health_trail {
+        hud.health_trail = health_ratio;
+        hud.trail_delay = TRAIL_DELAY;
+    } else if hud.trail_delay > 0. {
+        hud.trail_delay -= delta;
+    } else {
+        hud.health_trail = (hud.health_trail - TRAIL_SPEED * delta).max(health_ratio);
+    }
+
+    //Leveling up empties the bar before filling it with the leftover XP
+    if level.level != hud.level {
+        hud.level = level.level;
+        hud.xp_fill = 0.;
+    }
+
+    let target = xp_ratio(xp, progression, level);
+    hud.xp_fill = if hud.xp_fill < target {
+        (hud.xp_fill + XP_FILL_SPEED * delta).min(target)
+    } else {
+        target
+    };
+
+    if hud.hits_timer > 0. {
+        hud.hits_timer -= delta;
+    } else {
+        hud.hits = 0;
+    }
+}
+
+fn count_hits(
+    mut events: EventReader<HitEvent>,
+    mut hud: ResMut<HudState>,
+    player_query: Query<Entity, With<Player>>,
+) {
+    let Ok(player) = player_query.get_single() else {
+        events.clear();
+        return;
+    };
+
+    for event in events.read() {
+        if event.attacker == Some(player) {
+            hud.hits += 1;
+            hud.hits_timer = HIT_WINDOW;
+        }
+    }
+}
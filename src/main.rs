@@ -1,28 +1,49 @@
 use bevy::prelude::*;
 
-use game_states::{ingame::InGamePlugin, loading::LoadingPlugin, menu::MainMenuPlugin};
-use helper::KeyMaps;
+use controls::ControlsPlugin;
+use game_states::{
+    ingame::InGamePlugin, loading::LoadingPlugin, menu::MainMenuPlugin, summary::RunSummaryPlugin,
+};
+use settings::SettingsPlugin;
 use stats::*;
 
+mod achievements;
 mod animation;
+mod area;
 mod attack;
 mod boss;
 mod collision;
+#[cfg(feature = "debug")]
+mod console;
 mod controller;
+mod controls;
+mod damage;
+mod death;
 mod effects;
 mod enemy;
+mod feedback;
 mod floor;
 mod game_states;
+mod heal;
+mod health_bar;
 mod helper;
+mod hud;
 mod manifest;
 mod map;
+mod meta;
+mod minimap;
 mod movement;
 mod player;
+mod projectile;
+mod revenge;
+mod settings;
 mod sorting;
 mod state;
 mod statistics;
 mod stats;
+mod status;
 mod ui;
+mod weapon;
 
 pub const PLAYER_Z: f32 = 39.;
 pub const MAP_Z: f32 = 36.;
@@ -35,16 +56,40 @@ enum GameState {
     Loading,
     MainMenu,
     InGame,
+    RunSummary,
+}
+
+//Active during a run and its summary, the run's entities are despawned once it ends
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+struct InRun;
+
+impl ComputedStates for InRun {
+    type SourceStates = GameState;
+
+    fn compute(sources: GameState) -> Option<Self> {
+        matches!(sources, GameState::InGame | GameState::RunSummary).then_some(InRun)
+    }
 }
 
 fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
+    let mut app = App::new();
+
+    app.add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
         .insert_resource(ClearColor(Color::srgb(20. / 255., 0. / 255., 25. / 255.)))
-        .insert_resource(KeyMaps::default())
         .init_state::<GameState>()
+        .enable_state_scoped_entities::<GameState>()
+        .add_computed_state::<InRun>()
+        .enable_state_scoped_entities::<InRun>()
         .add_plugins(LoadingPlugin)
+        .add_plugins(ControlsPlugin)
+        .add_plugins(SettingsPlugin)
         .add_plugins(MainMenuPlugin)
         .add_plugins(InGamePlugin)
-        .run();
+        .add_plugins(RunSummaryPlugin);
+
+    //Developer console, only built with the debug feature
+    #[cfg(feature = "debug")]
+    app.add_plugins(console::ConsolePlugin);
+
+    app.run();
 }
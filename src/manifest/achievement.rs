@@ -0,0 +1,110 @@
+use std::convert::Infallible;
+
+use bevy::{asset::Asset, ecs::system::Resource, reflect::TypePath, utils::HashMap};
+use leafwing_manifest::{
+    identifier::Id,
+    manifest::{Manifest, ManifestFormat},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::statistics::Statistics;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub enum StatisticKind {
+    Kills,
+    Deaths,
+    Dashes,
+    DamageDealt,
+    DamageTaken,
+    MaxLevel,
+    PlayTime,
+    GameCount,
+    Healing,
+}
+
+impl StatisticKind {
+    pub fn value(&self, statistics: &Statistics) -> f32 {
+        match self {
+            StatisticKind::Kills => statistics.kills as f32,
+            StatisticKind::Deaths => statistics.deaths as f32,
+            StatisticKind::Dashes => statistics.dashes as f32,
+            StatisticKind::DamageDealt => statistics.damage_dealt as f32,
+            StatisticKind::DamageTaken => statistics.damage_taken as f32,
+            StatisticKind::MaxLevel => statistics.max_level as f32,
+            StatisticKind::PlayTime => statistics.play_time,
+            StatisticKind::GameCount => statistics.game_count as f32,
+            StatisticKind::Healing => statistics.healing as f32,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(tag = "type")]
+pub enum AchievementCondition {
+    Kills {
+        enemy: String,
+        count: u32,
+    },
+    Statistic {
+        stat: StatisticKind,
+        value: f32,
+    },
+    ReachFloor {
+        floor: u32,
+    },
+    FlawlessFloor, // Clear a floor without taking damage
+    DefeatBoss {
+        boss: String,
+        time_limit: Option<f32>, // Seconds since the floor started
+        #[serde(default)]
+        revenge: bool, // Must be in revenge mode
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct AchievementData {
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub hidden: bool, // Description is hidden until unlocked
+    pub condition: AchievementCondition,
+}
+
+#[derive(Debug, Asset, TypePath, Serialize, Deserialize, PartialEq)]
+pub struct RawAchievementManifest {
+    achievements: Vec<AchievementData>,
+}
+
+#[derive(Debug, Resource, PartialEq)]
+pub struct AchievementManifest {
+    pub achievements: HashMap<Id<AchievementData>, AchievementData>,
+}
+
+impl Manifest for AchievementManifest {
+    type RawManifest = RawAchievementManifest;
+
+    type RawItem = AchievementData;
+
+    type Item = AchievementData;
+
+    type ConversionError = Infallible;
+
+    const FORMAT: ManifestFormat = ManifestFormat::Yaml;
+
+    fn from_raw_manifest(
+        raw_manifest: Self::RawManifest,
+        _world: &mut bevy::prelude::World,
+    ) -> Result<Self, Self::ConversionError> {
+        let achievements = raw_manifest
+            .achievements
+            .iter()
+            .map(|raw| (Id::from_name(raw.name.as_str()), raw.clone()))
+            .collect();
+
+        Ok(AchievementManifest { achievements })
+    }
+
+    fn get(&self, id: Id<Self::Item>) -> Option<&Self::Item> {
+        self.achievements.get(&id)
+    }
+}
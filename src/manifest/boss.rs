@@ -14,9 +14,12 @@ use leafwing_manifest::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::animation::Animations;
+use crate::{animation::Animations, damage::DamageKind, death::DeathEffects};
 
-use super::{load_animations, load_texture_data, RawAnimationData, RawTextureData};
+use super::{
+    load_animations, load_attack_data, load_texture_data, AttackData, RawAnimationData,
+    RawAttackData, RawTextureData,
+};
 
 #[derive(Debug, Deserialize, Serialize, PartialEq)]
 pub struct RawBossData {
@@ -25,11 +28,17 @@ pub struct RawBossData {
     damage: u32,
     speed: u32,
     xp: u32,
+    cooldown: u32,
     hitbox: Vec2,
     scale: Vec2,
     feet_offset: Option<f32>,
+    #[serde(default)]
+    resistances: HashMap<DamageKind, f32>,
     texture: RawTextureData,
     animations: Vec<RawAnimationData>,
+    attack: Option<RawAttackData>,
+    #[serde(default)]
+    death: DeathEffects,
 }
 
 #[derive(Debug, PartialEq)]
@@ -39,12 +48,16 @@ pub struct BossData {
     pub damage: u32,
     pub speed: u32,
     pub xp: u32,
+    pub cooldown: u32,
     pub hitbox: Vec2,
     pub scale: Vec2,
     pub feet_offset: Option<f32>,
+    pub resistances: HashMap<DamageKind, f32>,
     pub texture: Handle<Image>,
     pub atlas: Handle<TextureAtlasLayout>,
     pub animations: Animations,
+    pub attack: Option<AttackData>,
+    pub death: DeathEffects,
 }
 
 #[derive(Debug, Asset, TypePath, Serialize, Deserialize, PartialEq)]
@@ -76,6 +89,10 @@ impl Manifest for BossManifest {
             .map(|raw_boss| {
                 let (texture, atlas) = load_texture_data(&raw_boss.texture, world);
                 let animations = load_animations(&raw_boss.name, &raw_boss.animations, world);
+                let attack = raw_boss
+                    .attack
+                    .as_ref()
+                    .map(|attack| load_attack_data(attack, world));
 
                 let enemy_data = BossData {
                     name: raw_boss.name.clone(),
@@ -83,12 +100,16 @@ impl Manifest for BossManifest {
                     damage: raw_boss.damage,
                     speed: raw_boss.speed,
                     xp: raw_boss.xp,
+                    cooldown: raw_boss.cooldown,
                     hitbox: raw_boss.hitbox,
                     scale: raw_boss.scale,
                     feet_offset: raw_boss.feet_offset,
+                    resistances: raw_boss.resistances.clone(),
                     texture,
                     atlas,
                     animations,
+                    attack,
+                    death: raw_boss.death,
                 };
 
                 (Id::from_name(raw_boss.name.as_str()), enemy_data)
@@ -16,7 +16,7 @@ use leafwing_manifest::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::animation::Animations;
+use crate::{animation::Animations, damage::DamageKind, death::DeathEffects};
 
 use super::{
     load_animations, load_attack_data, load_texture_data, AttackData, RawAnimationData,
@@ -34,9 +34,16 @@ pub struct RawEnemyData {
     hitbox: Vec2,
     scale: Vec2,
     feet_offset: Option<f32>,
+    health_orb: Option<(f32, u32)>, // (Drop chance, Heal amount)
+    #[serde(default)]
+    resistances: HashMap<DamageKind, f32>,
     attack: RawAttackData,
     texture: RawTextureData,
     animations: Vec<RawAnimationData>,
+    #[serde(default)]
+    death: DeathEffects,
+    #[serde(default)]
+    name_plate: bool, // Always shows its name, like bosses
 }
 
 #[derive(Debug, PartialEq)]
@@ -50,10 +57,14 @@ pub struct EnemyData {
     pub hitbox: Vec2,
     pub scale: Vec2,
     pub attack: AttackData,
+    pub health_orb: Option<(f32, u32)>,
+    pub resistances: HashMap<DamageKind, f32>,
     pub feet_offset: Option<f32>,
     pub texture: Handle<Image>,
     pub atlas: Handle<TextureAtlasLayout>,
     pub animations: Animations,
+    pub death: DeathEffects,
+    pub name_plate: bool,
 }
 
 #[derive(Debug, Asset, TypePath, Serialize, Deserialize, PartialEq)]
@@ -95,10 +106,14 @@ impl Manifest for EnemyManifest {
                     hitbox: raw_enemy.hitbox,
                     scale: raw_enemy.scale,
                     feet_offset: raw_enemy.feet_offset,
+                    health_orb: raw_enemy.health_orb,
+                    resistances: raw_enemy.resistances.clone(),
                     texture,
                     atlas,
                     attack: load_attack_data(&raw_enemy.attack, world),
                     animations: load_animations(&raw_enemy.name, &raw_enemy.animations, world),
+                    death: raw_enemy.death,
+                    name_plate: raw_enemy.name_plate,
                 };
 
                 (Id::from_name(raw_enemy.name.as_str()), enemy_data)
@@ -15,7 +15,9 @@ pub struct DomainData {
     pub room_size: (u32, u32),
     pub boss: String, //Boss name
     pub enemies_count: (u32, u32),
-    pub enemies: Vec<(u32, String)>, // [(Spawn Weight, Enemy Name)]
+    pub enemies: Vec<(u32, String)>,   // [(Spawn Weight, Enemy Name)]
+    pub fountains: Option<(f32, u32)>, // (Spawn chance per room, Heal amount)
+    pub weapons: Option<f32>,          // Spawn chance per room of a random weapon
 }
 
 #[derive(Debug, Asset, TypePath, Serialize, Deserialize, PartialEq)]
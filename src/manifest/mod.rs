@@ -11,7 +11,7 @@ use bevy::{
 };
 use bevy_spritesheet_animation::{
     clip::Clip,
-    prelude::{Animation, AnimationDuration, AnimationId, AnimationLibrary},
+    prelude::{Animation, AnimationDuration, AnimationId, AnimationLibrary, AnimationRepeat},
 };
 use boss::BossManifest;
 use leafwing_manifest::{
@@ -22,16 +22,27 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     animation::{Animations, DirectionalAnimations},
+    area::AreaKind,
+    damage::DamageKind,
+    feedback::HitFeedback,
     movement::direction::Direction,
+    projectile::ProjectilePattern,
     state::State,
+    status::StatusEffectData,
 };
 
-use self::{enemy::EnemyManifest, floor::DomainManifest, player::PlayerManifest};
+use self::{
+    achievement::AchievementManifest, enemy::EnemyManifest, floor::DomainManifest,
+    player::PlayerManifest, unlock::UnlockManifest, weapon::WeaponManifest,
+};
 
+pub mod achievement;
 pub mod boss;
 pub mod enemy;
 pub mod floor;
 pub mod player;
+pub mod unlock;
+pub mod weapon;
 
 pub struct DataManifestPlugin {}
 
@@ -42,7 +53,10 @@ impl Plugin for DataManifestPlugin {
             .register_manifest::<EnemyManifest>("entities/enemies/data.yaml")
             .register_manifest::<PlayerManifest>("entities/player/player.yaml")
             .register_manifest::<DomainManifest>("domains.yaml")
-            .register_manifest::<BossManifest>("entities/enemies/bosses.yaml");
+            .register_manifest::<BossManifest>("entities/enemies/bosses.yaml")
+            .register_manifest::<UnlockManifest>("unlocks.yaml")
+            .register_manifest::<AchievementManifest>("achievements.yaml")
+            .register_manifest::<WeaponManifest>("weapons.yaml");
     }
 }
 
@@ -63,12 +77,37 @@ pub enum RawAttackData {
         size: Vec2,
         duration: f32,
         knockback: f32,
+        #[serde(default)]
+        damage_kind: DamageKind,
+        #[serde(default)]
+        effects: Vec<StatusEffectData>,
+        #[serde(default)]
+        feedback: HitFeedback,
     },
     Ranged {
         size: Vec2,
         duration: f32,
         velocity: f32,
         texture: RawTextureData,
+        #[serde(default)]
+        damage_kind: DamageKind,
+        #[serde(default)]
+        effects: Vec<StatusEffectData>,
+        #[serde(default)]
+        feedback: HitFeedback,
+        #[serde(default)]
+        pattern: ProjectilePattern,
+    },
+    Area {
+        area: AreaKind,
+        #[serde(default)]
+        at_target: bool, // Spawns on the player instead of in front of the enemy
+        #[serde(default)]
+        damage_kind: DamageKind,
+        #[serde(default)]
+        effects: Vec<StatusEffectData>,
+        #[serde(default)]
+        feedback: HitFeedback,
     },
 }
 
@@ -78,6 +117,9 @@ pub enum AttackData {
         size: Vec2,
         duration: f32,
         knockback: f32,
+        damage_kind: DamageKind,
+        effects: Vec<StatusEffectData>,
+        feedback: HitFeedback,
     },
     Ranged {
         size: Vec2,
@@ -85,6 +127,17 @@ pub enum AttackData {
         velocity: f32,
         texture: Handle<Image>,
         atlas: Handle<TextureAtlasLayout>,
+        damage_kind: DamageKind,
+        effects: Vec<StatusEffectData>,
+        feedback: HitFeedback,
+        pattern: ProjectilePattern,
+    },
+    Area {
+        area: AreaKind,
+        at_target: bool,
+        damage_kind: DamageKind,
+        effects: Vec<StatusEffectData>,
+        feedback: HitFeedback,
     },
 }
 
@@ -115,6 +168,10 @@ pub fn load_attack_data(data: &RawAttackData, world: &mut bevy::prelude::World)
             duration: raw_duration,
             velocity: raw_velocity,
             texture: raw_texture,
+            damage_kind: raw_damage_kind,
+            effects: raw_effects,
+            feedback: raw_feedback,
+            pattern: raw_pattern,
         } => {
             let (texture, atlas) = load_texture_data(&raw_texture, world);
             AttackData::Ranged {
@@ -123,16 +180,39 @@ pub fn load_attack_data(data: &RawAttackData, world: &mut bevy::prelude::World)
                 velocity: *raw_velocity,
                 texture,
                 atlas,
+                damage_kind: *raw_damage_kind,
+                effects: raw_effects.clone(),
+                feedback: *raw_feedback,
+                pattern: *raw_pattern,
             }
         }
         RawAttackData::Melee {
             size: raw_size,
             duration: raw_duration,
             knockback: raw_knockback,
+            damage_kind: raw_damage_kind,
+            effects: raw_effects,
+            feedback: raw_feedback,
         } => AttackData::Melee {
             size: *raw_size,
             duration: *raw_duration,
             knockback: *raw_knockback,
+            damage_kind: *raw_damage_kind,
+            effects: raw_effects.clone(),
+            feedback: *raw_feedback,
+        },
+        RawAttackData::Area {
+            area,
+            at_target,
+            damage_kind,
+            effects,
+            feedback,
+        } => AttackData::Area {
+            area: *area,
+            at_target: *at_target,
+            damage_kind: *damage_kind,
+            effects: effects.clone(),
+            feedback: *feedback,
         },
     }
 }
@@ -142,6 +222,7 @@ pub struct RawAnimationData {
     pub name: String,
     pub frames: Vec<usize>,
     pub duration: u32,
+    pub repeat: Option<usize>, // Times it plays before ending, loops forever if missing
 }
 
 pub fn load_animations(
@@ -160,7 +241,11 @@ pub fn load_animations(
                     Clip::from_frames(raw.frames.clone())
                         .with_duration(AnimationDuration::PerFrame(raw.duration)),
                 );
-                let animation_id = library.register_animation(Animation::from_clip(clip_id));
+                let repeat = raw
+                    .repeat
+                    .map_or(AnimationRepeat::Loop, AnimationRepeat::Times);
+                let animation_id = library
+                    .register_animation(Animation::from_clip(clip_id).with_repetitions(repeat));
 
                 library
                     .name_animation(animation_id, format!("{}_{}", name, raw.name))
@@ -7,17 +7,67 @@ use bevy::{
     reflect::TypePath,
     render::texture::Image,
     sprite::TextureAtlasLayout,
+    utils::HashMap,
 };
 
 use leafwing_manifest::manifest::{Manifest, ManifestFormat};
 use serde::{Deserialize, Serialize};
 
-use crate::animation::DirectionalAnimations;
+use crate::{
+    animation::DirectionalAnimations,
+    attack::ChargedAttack,
+    damage::{DamageKind, HitReaction},
+    revenge::RevengeEffect,
+};
 
 use super::{
     load_directional_animations, load_texture_data, RawDirectionalAnimationData, RawTextureData,
 };
 
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RawSecondaryData {
+    damage_multiplier: f32,
+    velocity: f32,
+    lifetime: f32,
+    cooldown: f32, // Seconds
+    size: Vec2,
+    texture: RawTextureData,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SecondaryData {
+    pub damage_multiplier: f32,
+    pub velocity: f32,
+    pub lifetime: f32,
+    pub cooldown: f32,
+    pub size: Vec2,
+    pub texture: Handle<Image>,
+    pub atlas: Handle<TextureAtlasLayout>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct ParryData {
+    pub animation: u32, // Index of the Attacking animation to play
+    pub window: f32,    // Seconds enemy attacks are parried for
+    pub recover: f32,   // Seconds the player can't act after a missed parry
+    pub cooldown: f32,
+    pub stun: f32,    // Seconds the attacker is stunned for
+    pub revenge: f32, // Revenge gained on a successful parry
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct RevengeData {
+    pub total: f32, // Revenge needed to activate
+    pub decay: f32,
+    pub active_decay: f32,
+    pub damage_multiplier: f32,
+    pub speed_multiplier: f32,
+    #[serde(default)]
+    pub manual: bool,
+    #[serde(default)]
+    pub effects: Vec<RevengeEffect>,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct RawPlayerData {
     size: Vec2,
@@ -28,8 +78,21 @@ pub struct RawPlayerData {
     xp: u32,
     base_xp: u32,
     xp_multiplier: f32,
+    lifesteal: Option<f32>,
+    lifesteal_per_level: Option<f32>,
+    weapon: String, // Weapon the player starts with
+    secondary: RawSecondaryData,
+    heavy: ChargedAttack,
+    parry: ParryData,
+    revenge: RevengeData,
+    hit_reaction: HitReaction,
+    crit_chance: Option<f32>,
+    crit_multiplier: Option<f32>,
+    #[serde(default)]
+    resistances: HashMap<DamageKind, f32>,
     hitbox: Vec2,
     feet_offset: Option<f32>,
+    input_buffer: Option<f32>, // Seconds attack and dash presses are remembered
     texture: RawTextureData,
     animations: Vec<RawDirectionalAnimationData>,
 }
@@ -44,8 +107,20 @@ pub struct PlayerData {
     pub xp: u32,
     pub base_xp: u32,
     pub xp_multiplier: f32,
+    pub lifesteal: Option<f32>,
+    pub lifesteal_per_level: Option<f32>,
+    pub weapon: String,
+    pub secondary: SecondaryData,
+    pub heavy: ChargedAttack,
+    pub parry: ParryData,
+    pub revenge: RevengeData,
+    pub hit_reaction: HitReaction,
+    pub crit_chance: Option<f32>,
+    pub crit_multiplier: Option<f32>,
+    pub resistances: HashMap<DamageKind, f32>,
     pub hitbox: Vec2,
     pub feet_offset: Option<f32>,
+    pub input_buffer: Option<f32>,
     pub texture: Handle<Image>,
     pub atlas: Handle<TextureAtlasLayout>,
     pub animations: DirectionalAnimations,
@@ -77,6 +152,8 @@ impl Manifest for PlayerManifest {
         let raw_data = raw_manifest.0;
 
         let (texture, atlas) = load_texture_data(&raw_data.texture, world);
+        let (secondary_texture, secondary_atlas) =
+            load_texture_data(&raw_data.secondary.texture, world);
 
         let player_data = PlayerData {
             size: raw_data.size,
@@ -87,8 +164,28 @@ impl Manifest for PlayerManifest {
             xp: raw_data.xp,
             base_xp: raw_data.base_xp,
             xp_multiplier: raw_data.xp_multiplier,
+            lifesteal: raw_data.lifesteal,
+            lifesteal_per_level: raw_data.lifesteal_per_level,
+            weapon: raw_data.weapon,
+            secondary: SecondaryData {
+                damage_multiplier: raw_data.secondary.damage_multiplier,
+                velocity: raw_data.secondary.velocity,
+                lifetime: raw_data.secondary.lifetime,
+                cooldown: raw_data.secondary.cooldown,
+                size: raw_data.secondary.size,
+                texture: secondary_texture,
+                atlas: secondary_atlas,
+            },
+            heavy: raw_data.heavy,
+            parry: raw_data.parry,
+            revenge: raw_data.revenge,
+            hit_reaction: raw_data.hit_reaction,
+            crit_chance: raw_data.crit_chance,
+            crit_multiplier: raw_data.crit_multiplier,
+            resistances: raw_data.resistances,
             hitbox: raw_data.hitbox,
             feet_offset: raw_data.feet_offset,
+            input_buffer: raw_data.input_buffer,
             texture,
             atlas,
             animations: load_directional_animations(&raw_data.animations, world),
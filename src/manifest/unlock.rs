@@ -0,0 +1,79 @@
+use std::convert::Infallible;
+
+use bevy::{asset::Asset, ecs::system::Resource, reflect::TypePath, utils::HashMap};
+use leafwing_manifest::{
+    identifier::Id,
+    manifest::{Manifest, ManifestFormat},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(tag = "type")]
+pub enum UnlockEffect {
+    Health { amount: u32 },
+    Damage { amount: u32 },
+    Speed { amount: u32 },
+    Lifesteal { amount: f32 },
+    RevengeDecay { amount: f32 },      // Reduces the revenge decay
+    RevengeTotal { amount: f32 },      // Reduces the revenge needed to activate
+    StartingWeapon { weapon: String }, // Weapon in the second slot at the start of a run
+    Revive { health: f32 },            // Part of the max health restored once per run
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct UnlockData {
+    pub name: String,
+    pub description: String,
+    pub costs: Vec<u32>, // Cost of each level, the length is the max level
+    pub requires: Option<String>, // Unlock name that needs to be bought first
+    pub effect: UnlockEffect,
+}
+
+impl UnlockData {
+    pub fn max_level(&self) -> u32 {
+        self.costs.len() as u32
+    }
+
+    pub fn cost(&self, level: u32) -> Option<u32> {
+        self.costs.get(level as usize).copied()
+    }
+}
+
+#[derive(Debug, Asset, TypePath, Serialize, Deserialize, PartialEq)]
+pub struct RawUnlockManifest {
+    unlocks: Vec<UnlockData>,
+}
+
+#[derive(Debug, Resource, PartialEq)]
+pub struct UnlockManifest {
+    pub unlocks: HashMap<Id<UnlockData>, UnlockData>,
+}
+
+impl Manifest for UnlockManifest {
+    type RawManifest = RawUnlockManifest;
+
+    type RawItem = UnlockData;
+
+    type Item = UnlockData;
+
+    type ConversionError = Infallible;
+
+    const FORMAT: ManifestFormat = ManifestFormat::Yaml;
+
+    fn from_raw_manifest(
+        raw_manifest: Self::RawManifest,
+        _world: &mut bevy::prelude::World,
+    ) -> Result<Self, Self::ConversionError> {
+        let unlocks = raw_manifest
+            .unlocks
+            .iter()
+            .map(|raw| (Id::from_name(raw.name.as_str()), raw.clone()))
+            .collect();
+
+        Ok(UnlockManifest { unlocks })
+    }
+
+    fn get(&self, id: Id<Self::Item>) -> Option<&Self::Item> {
+        self.unlocks.get(&id)
+    }
+}
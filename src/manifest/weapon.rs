@@ -0,0 +1,93 @@
+use std::convert::Infallible;
+
+use bevy::{
+    asset::{Asset, Handle},
+    ecs::system::Resource,
+    reflect::TypePath,
+    render::texture::Image,
+    sprite::TextureAtlasLayout,
+    utils::HashMap,
+};
+use leafwing_manifest::{
+    identifier::Id,
+    manifest::{Manifest, ManifestFormat},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{animation::DirectionalAnimations, attack::ComboStep};
+
+use super::{
+    load_directional_animations, load_texture_data, RawDirectionalAnimationData, RawTextureData,
+};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RawWeaponData {
+    name: String,
+    description: String,
+    combo_window: f32,
+    combo: Vec<ComboStep>,
+    projectile: Option<RawTextureData>, // Texture for Projectile steps
+    #[serde(default)]
+    animations: Vec<RawDirectionalAnimationData>, // Replaces the player animations of the same state
+}
+
+#[derive(Debug, PartialEq)]
+pub struct WeaponData {
+    pub name: String,
+    pub description: String,
+    pub combo_window: f32,
+    pub combo: Vec<ComboStep>,
+    pub projectile: Option<(Handle<Image>, Handle<TextureAtlasLayout>)>,
+    pub animations: DirectionalAnimations,
+}
+
+#[derive(Debug, Asset, TypePath, Serialize, Deserialize, PartialEq)]
+pub struct RawWeaponManifest {
+    weapons: Vec<RawWeaponData>,
+}
+
+#[derive(Debug, Resource, PartialEq)]
+pub struct WeaponManifest {
+    pub weapons: HashMap<Id<WeaponData>, WeaponData>,
+}
+
+impl Manifest for WeaponManifest {
+    type Item = WeaponData;
+    type RawItem = RawWeaponData;
+    type RawManifest = RawWeaponManifest;
+
+    type ConversionError = Infallible;
+
+    const FORMAT: ManifestFormat = ManifestFormat::Yaml;
+
+    fn from_raw_manifest(
+        raw_manifest: Self::RawManifest,
+        world: &mut bevy::prelude::World,
+    ) -> Result<Self, Self::ConversionError> {
+        let weapons = raw_manifest
+            .weapons
+            .iter()
+            .map(|raw_weapon| {
+                let weapon_data = WeaponData {
+                    name: raw_weapon.name.clone(),
+                    description: raw_weapon.description.clone(),
+                    combo_window: raw_weapon.combo_window,
+                    combo: raw_weapon.combo.clone(),
+                    projectile: raw_weapon
+                        .projectile
+                        .as_ref()
+                        .map(|texture| load_texture_data(texture, world)),
+                    animations: load_directional_animations(&raw_weapon.animations, world),
+                };
+
+                (Id::from_name(raw_weapon.name.as_str()), weapon_data)
+            })
+            .collect();
+
+        Ok(WeaponManifest { weapons })
+    }
+
+    fn get(&self, id: Id<Self::Item>) -> Option<&Self::Item> {
+        self.weapons.get(&id)
+    }
+}
@@ -8,6 +8,7 @@ use crate::map::map::generate_map;
 use crate::map::map::Tile;
 use crate::map::map::TileVariant;
 use crate::map::walkable::WalkableTile;
+use crate::InRun;
 
 #[derive(Component)]
 pub struct LevelStartTile;
@@ -18,7 +19,7 @@ pub struct LevelPortalTile;
 pub fn setup_map(mut commands: Commands, game_assets: Res<GameAssets>) {
     let tilemap_size = TilemapSize { x: 160, y: 160 };
     let mut tile_storage = TileStorage::empty(tilemap_size);
-    let tilemap_entity = commands.spawn_empty().id();
+    let tilemap_entity = commands.spawn(StateScoped(InRun)).id();
     let tilemap_id = TilemapId(tilemap_entity);
 
     let tile_size = TilemapTileSize { x: 32.0, y: 32.0 };
@@ -67,7 +68,7 @@ pub fn remake_map(
             }
 
             if let Ok(tile_storage) = tile_storage_query.get_single() {
-                let map = generate_map(domain_data);
+                let map = generate_map(domain_data, floor.rng());
                 let tiles: Vec<Tile> = map.into();
                 let spawn_event = build_map(tiles, &mut tile_query, tile_storage, &mut commands);
 
@@ -86,6 +87,7 @@ fn build_map(
     let mut player_pos = Vec2::ZERO;
     let mut spawnable_pos = Vec::new();
     let mut portal_pos = Vec2::ZERO;
+    let mut room_centers = Vec::new();
 
     for tile in &tiles {
         let tile_pos = TilePos {
@@ -125,6 +127,8 @@ fn build_map(
                 } else if tile.last_room {
                     ec.insert(LevelPortalTile);
                     portal_pos = world_pos;
+                } else {
+                    room_centers.push(world_pos);
                 }
             }
         }
@@ -134,6 +138,7 @@ fn build_map(
         spawnable_pos,
         player_pos,
         portal_pos,
+        room_centers,
     }
 }
 
@@ -100,7 +100,7 @@ impl Into<Vec<Tile>> for Map {
         for tile in &mut tiles {
             let mut empty_neighbors = Vec::<Direction>::new();
 
-            for direction in Direction::values() {
+            for direction in Direction::cardinal() {
                 let neighbor = tiles_copy
                     .iter()
                     .find(|neighbor| neighbor.pos == tile.pos + direction.vec().as_ivec2());
@@ -121,8 +121,7 @@ impl Into<Vec<Tile>> for Map {
     }
 }
 
-pub fn generate_map(domain_data: &DomainData) -> Map {
-    let mut rand = Rng::new();
+pub fn generate_map(domain_data: &DomainData, mut rand: Rng) -> Map {
     let mut rooms = Vec::<Room>::new();
     let mut bridges = Vec::<Bridge>::new();
 
@@ -12,6 +12,19 @@ use crate::{controller::Controlled, state::State};
 #[derive(Component)]
 pub struct WalkableTile;
 
+//Tiles are 32x32 on a 32x16 grid, positions are moved down half a tile to match them
+const TILE_OFFSET: Vec2 = Vec2::new(0., -16.);
+
+//Tile an entity at a world position stands on
+pub fn tile_at(
+    position: Vec2,
+    map_size: &TilemapSize,
+    grid_size: &TilemapGridSize,
+    map_type: &TilemapType,
+) -> Option<TilePos> {
+    TilePos::from_world_pos(&(position + TILE_OFFSET), map_size, grid_size, map_type)
+}
+
 pub fn restrict_movement(
     mut controlled_query: Query<(&Controlled, &mut Transform, Option<&State>)>,
     query: Query<(&TileStorage, &TilemapType, &TilemapSize, &TilemapGridSize)>,
@@ -20,12 +33,7 @@ pub fn restrict_movement(
     if let Some((tile_storage, tilemap_type, map_size, grid_size)) = query.iter().next() {
         for (controlled, mut transform, state) in controlled_query.iter_mut() {
             if let Some(move_to) = controlled.move_to {
-                let grid_pos = move_to + Vec2::new(0., -16.); //Account for the tile being 32x32 on a
-                                                              //32x16 grid
-
-                if let Some(tile_pos) =
-                    TilePos::from_world_pos(&grid_pos, map_size, grid_size, tilemap_type)
-                {
+                if let Some(tile_pos) = tile_at(move_to, map_size, grid_size, tilemap_type) {
                     //Don't move if the player doesn't want to move
                     if let Some(state) = state {
                         if !state.equals(State::Walking) {
@@ -54,10 +62,9 @@ pub fn travel_through_portal(
 ) {
     if let Some((tile_storage, tilemap_type, map_size, grid_size)) = query.iter().next() {
         for transform in controlled_query.iter() {
-            let pos = transform.translation.xy() + Vec2::new(0., -16.); //Account for the tile being 32x32 on a
-                                                                        //32x16 grid
-            if let Some(tile_pos) = TilePos::from_world_pos(&pos, map_size, grid_size, tilemap_type)
-            {
+            let pos = transform.translation.xy();
+
+            if let Some(tile_pos) = tile_at(pos, map_size, grid_size, tilemap_type) {
                 if let Some(tile_entity) = tile_storage.get(&tile_pos) {
                     if portal_query.get(tile_entity).is_ok() {
                         *timer += delta.delta_seconds();
@@ -72,3 +79,61 @@ pub fn travel_through_portal(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs_tilemap::map::IsoCoordSystem;
+
+    use super::*;
+
+    const MAP_SIZE: TilemapSize = TilemapSize { x: 160, y: 160 };
+    const GRID_SIZE: TilemapGridSize = TilemapGridSize { x: 32., y: 16. };
+    const MAP_TYPE: TilemapType = TilemapType::Isometric(IsoCoordSystem::Diamond);
+
+    //World position an entity standing in the middle of the tile has
+    fn standing_on(tile: TilePos) -> Vec2 {
+        tile.center_in_world(&GRID_SIZE, &MAP_TYPE) - TILE_OFFSET
+    }
+
+    #[test]
+    fn entities_stand_on_the_tile_below_them() {
+        for tile in [
+            TilePos { x: 0, y: 0 },
+            TilePos { x: 10, y: 42 },
+            TilePos { x: 80, y: 80 },
+            TilePos { x: 159, y: 3 },
+        ] {
+            let position = standing_on(tile);
+
+            assert_eq!(
+                tile_at(position, &MAP_SIZE, &GRID_SIZE, &MAP_TYPE),
+                Some(tile)
+            );
+        }
+    }
+
+    #[test]
+    fn nearby_positions_stay_on_the_same_tile() {
+        let tile = TilePos { x: 20, y: 20 };
+        let position = standing_on(tile);
+
+        for offset in [
+            Vec2::new(6., 0.),
+            Vec2::new(-6., 0.),
+            Vec2::new(0., 3.),
+            Vec2::new(0., -3.),
+        ] {
+            assert_eq!(
+                tile_at(position + offset, &MAP_SIZE, &GRID_SIZE, &MAP_TYPE),
+                Some(tile)
+            );
+        }
+    }
+
+    #[test]
+    fn positions_outside_the_map_have_no_tile() {
+        let position = standing_on(TilePos { x: 0, y: 0 }) - Vec2::new(0., 64.);
+
+        assert_eq!(tile_at(position, &MAP_SIZE, &GRID_SIZE, &MAP_TYPE), None);
+    }
+}
@@ -0,0 +1,141 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_persistent::prelude::*;
+use leafwing_manifest::manifest::Manifest;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    death::Revive,
+    floor::{FloorResource, RunEndedEvent},
+    heal::Lifesteal,
+    manifest::unlock::{UnlockData, UnlockEffect, UnlockManifest},
+    player::Player,
+    stats::{Damage, Health, MovementSpeed, Revenge},
+    weapon::Weapons,
+    GameState,
+};
+
+const CURRENCY_PER_FLOOR: u32 = 10;
+const VICTORY_BONUS: u32 = 250;
+
+pub struct MetaPlugin;
+
+impl Plugin for MetaPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, award_currency.run_if(in_state(GameState::InGame)));
+    }
+}
+
+//Progression that carries over between runs
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MetaProgression {
+    pub currency: u32,
+    pub unlocks: HashMap<String, u32>, // Unlock name -> Level bought
+}
+
+impl MetaProgression {
+    pub fn level(&self, unlock: &str) -> u32 {
+        self.unlocks.get(unlock).copied().unwrap_or_default()
+    }
+
+    pub fn can_buy(&self, unlock: &UnlockData) -> bool {
+        if let Some(required) = &unlock.requires {
+            if self.level(required) == 0 {
+                return false;
+            }
+        }
+
+        unlock
+            .cost(self.level(&unlock.name))
+            .is_some_and(|cost| cost <= self.currency)
+    }
+
+    pub fn buy(&mut self, unlock: &UnlockData) -> bool {
+        if !self.can_buy(unlock) {
+            return false;
+        }
+
+        let level = self.level(&unlock.name);
+        self.currency -= unlock.cost(level).unwrap_or_default();
+        self.unlocks.insert(unlock.name.clone(), level + 1);
+
+        true
+    }
+}
+
+pub fn award_currency(
+    mut events: EventReader<RunEndedEvent>,
+    mut meta: ResMut<Persistent<MetaProgression>>,
+    floor: Res<FloorResource>,
+) {
+    for event in events.read() {
+        let mut earned = floor.floor * CURRENCY_PER_FLOOR;
+
+        if event.victory {
+            earned += VICTORY_BONUS;
+        }
+
+        meta.currency += earned;
+        let _ = meta.persist();
+    }
+}
+
+//Applies the bought unlocks to a freshly spawned player
+pub fn apply_unlocks(
+    meta: Res<Persistent<MetaProgression>>,
+    unlock_manifest: Res<UnlockManifest>,
+    mut query: Query<
+        (
+            &mut Health,
+            &mut Damage,
+            &mut MovementSpeed,
+            &mut Lifesteal,
+            &mut Revenge,
+            &mut Weapons,
+            Entity,
+        ),
+        With<Player>,
+    >,
+    mut commands: Commands,
+) {
+    let Ok((mut health, mut damage, mut speed, mut lifesteal, mut revenge, mut weapons, entity)) =
+        query.get_single_mut()
+    else {
+        return;
+    };
+
+    for (name, level) in meta.unlocks.iter() {
+        let Some(unlock) = unlock_manifest.get_by_name(name.clone()) else {
+            continue;
+        };
+
+        let level = *level;
+
+        match &unlock.effect {
+            UnlockEffect::Health { amount } => {
+                health.max += amount * level;
+                health.current = health.max;
+            }
+            UnlockEffect::Damage { amount } => damage.amount += amount * level,
+            UnlockEffect::Speed { amount } => speed.speed += amount * level,
+            UnlockEffect::Lifesteal { amount } => lifesteal.0 += amount * level as f32,
+            UnlockEffect::RevengeDecay { amount } => {
+                revenge.decay = (revenge.decay - amount * level as f32).max(0.);
+            }
+            UnlockEffect::RevengeTotal { amount } => {
+                revenge.total = (revenge.total - amount * level as f32).max(1.);
+            }
+            UnlockEffect::StartingWeapon { weapon } => {
+                if level > 0 {
+                    weapons.slots[1] = Some(weapon.clone());
+                }
+            }
+            UnlockEffect::Revive { health } => {
+                if level > 0 {
+                    commands.entity(entity).insert(Revive {
+                        health: health * level as f32,
+                    });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use bevy::{
+    math::Vec3Swizzles,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_ecs_tilemap::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+    floor::GenerateFloorEvent,
+    map::{
+        generation::{LevelPortalTile, LevelStartTile},
+        walkable::{tile_at, WalkableTile},
+    },
+    player::{Player, PlayerActions},
+    ui::minimap::{draw_map_overlay, draw_minimap},
+    GameState,
+};
+
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Minimap>().add_systems(
+            Update,
+            (
+                (track_tiles, reveal_tiles).chain(),
+                toggle_map,
+                draw_minimap,
+                draw_map_overlay,
+            )
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+//Tiles around the player revealed each frame
+const REVEAL_RADIUS: i32 = 7;
+
+//Layout of the current floor and the parts the player has explored
+#[derive(Resource, Default)]
+pub struct Minimap {
+    pub tiles: HashMap<TilePos, Vec2>, // Walkable tile -> World position of its center
+    pub revealed: HashSet<TilePos>,
+    pub start: Option<TilePos>,
+    pub portal: Option<TilePos>,
+    pub open: bool, // Full screen map shown
+    layout: Option<(TilemapSize, TilemapGridSize, TilemapType)>, // Of the floor's tilemap
+}
+
+impl Minimap {
+    //Tile under a world position, the same one the movement checks use
+    pub fn tile_at(&self, position: Vec2) -> Option<TilePos> {
+        let (map_size, grid_size, map_type) = self.layout.as_ref()?;
+
+        tile_at(position, map_size, grid_size, map_type)
+    }
+
+    pub fn is_revealed(&self, position: Vec2) -> bool {
+        self.tile_at(position)
+            .is_some_and(|tile| self.revealed.contains(&tile))
+    }
+
+    //World space rectangle containing every tile of the floor
+    pub fn bounds(&self) -> Option<Rect> {
+        let mut tiles = self.tiles.values();
+        let first = tiles.next()?;
+
+        Some(
+            tiles.fold(Rect::from_center_size(*first, Vec2::ZERO), |rect, pos| {
+                rect.union_point(*pos)
+            }),
+        )
+    }
+}
+
+//The tiles are inserted with commands while the floor is built, so they are picked up once added
+fn track_tiles(
+    mut events: EventReader<GenerateFloorEvent>,
+    mut minimap: ResMut<Minimap>,
+    walkable_query: Query<&TilePos, Added<WalkableTile>>,
+    start_query: Query<&TilePos, Added<LevelStartTile>>,
+    portal_query: Query<&TilePos, Added<LevelPortalTile>>,
+    tilemap_query: Query<(&TilemapSize, &TilemapGridSize, &TilemapType)>,
+) {
+    if events.read().last().is_some() {
+        *minimap = Minimap::default();
+    }
+
+    let Some((map_size, grid_size, map_type)) = tilemap_query.iter().next() else {
+        return;
+    };
+
+    minimap.layout = Some((*map_size, *grid_size, *map_type));
+
+    for tile_pos in walkable_query.iter() {
+        let world_pos = tile_pos.center_in_world(grid_size, map_type);
+
+        minimap.tiles.insert(*tile_pos, world_pos);
+    }
+
+    if let Some(tile_pos) = start_query.iter().next() {
+        minimap.start = Some(*tile_pos);
+    }
+
+    if let Some(tile_pos) = portal_query.iter().next() {
+        minimap.portal = Some(*tile_pos);
+    }
+}
+
+fn reveal_tiles(mut minimap: ResMut<Minimap>, player_query: Query<&Transform, With<Player>>) {
+    let Ok(transform) = player_query.get_single() else {
+        return;
+    };
+
+    let Some(center) = minimap.tile_at(transform.translation.xy()) else {
+        return;
+    };
+
+    for x in -REVEAL_RADIUS..=REVEAL_RADIUS {
+        for y in -REVEAL_RADIUS..=REVEAL_RADIUS {
+            if x * x + y * y > REVEAL_RADIUS * REVEAL_RADIUS {
+                continue;
+            }
+
+            let (Some(x), Some(y)) = (
+                center.x.checked_add_signed(x),
+                center.y.checked_add_signed(y),
+            ) else {
+                continue;
+            };
+
+            let tile_pos = TilePos { x, y };
+
+            if minimap.tiles.contains_key(&tile_pos) && !minimap.revealed.contains(&tile_pos) {
+                minimap.revealed.insert(tile_pos);
+            }
+        }
+    }
+}
+
+fn toggle_map(
+    mut minimap: ResMut<Minimap>,
+    player_query: Query<&ActionState<PlayerActions>, With<Player>>,
+) {
+    let Ok(action_state) = player_query.get_single() else {
+        return;
+    };
+
+    if action_state.just_pressed(&PlayerActions::Map) {
+        minimap.open = !minimap.open;
+    }
+}
@@ -1,41 +1,74 @@
+use std::f32::consts::FRAC_1_SQRT_2;
+
 use bevy::{math::Vec2, prelude::Component};
 use serde::{Deserialize, Serialize};
 
 #[derive(Component, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Direction {
-    NORTH,
-    SOUTH,
-    WEST,
-    EAST,
+    North,
+    South,
+    West,
+    East,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
 }
 
 impl Direction {
     pub fn vec(&self) -> Vec2 {
         match *self {
-            Self::NORTH => Vec2::Y,
-            Self::SOUTH => -Vec2::Y,
-            Self::WEST => -Vec2::X,
-            Self::EAST => Vec2::X,
+            Self::North => Vec2::Y,
+            Self::South => -Vec2::Y,
+            Self::West => -Vec2::X,
+            Self::East => Vec2::X,
+            Self::NorthEast => Vec2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+            Self::NorthWest => Vec2::new(-FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+            Self::SouthEast => Vec2::new(FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+            Self::SouthWest => Vec2::new(-FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
         }
     }
 
     pub fn from_vec2(vec: Vec2) -> Option<Self> {
-        if vec.x > 0.0 {
-            Some(Self::EAST)
-        } else if vec.x < 0.0 {
-            Some(Self::WEST)
-        } else if vec.y > 0.0 {
-            Some(Self::NORTH)
-        } else if vec.y < 0.0 {
-            Some(Self::SOUTH)
-        } else {
+        if vec == Vec2::ZERO {
             None
+        } else {
+            Some(Self::closest(vec))
         }
     }
 
-    #[allow(dead_code)]
-    pub fn values() -> [Self; 4] {
-        [Self::NORTH, Self::SOUTH, Self::WEST, Self::EAST]
+    pub fn values() -> [Self; 8] {
+        [
+            Self::North,
+            Self::South,
+            Self::West,
+            Self::East,
+            Self::NorthEast,
+            Self::NorthWest,
+            Self::SouthEast,
+            Self::SouthWest,
+        ]
+    }
+
+    pub fn cardinal() -> [Self; 4] {
+        [Self::North, Self::South, Self::West, Self::East]
+    }
+
+    //Direction that best matches any vector
+    pub fn closest(vec: Vec2) -> Self {
+        Self::closest_of(vec, Self::values()).unwrap_or(Self::South)
+    }
+
+    //Direction out of the given ones that best matches the vector, ties go to the first one given
+    pub fn closest_of(vec: Vec2, directions: impl IntoIterator<Item = Self>) -> Option<Self> {
+        directions.into_iter().reduce(|best, direction| {
+            if direction.vec().dot(vec) > best.vec().dot(vec) {
+                direction
+            } else {
+                best
+            }
+        })
     }
 
     pub fn set(&mut self, direction: Direction) {
@@ -1,4 +1,5 @@
 use crate::map::walkable::WalkableTile;
+use crate::status::StatusEffects;
 use crate::GameState;
 use bevy::math::{Vec2, Vec3Swizzles};
 use bevy::prelude::{
@@ -50,11 +51,11 @@ impl Follow {
 //System for an entity to follow another
 pub fn follow_entity_system(
     mut commands: Commands,
-    mut query_followers: Query<(&mut Follow, Entity)>,
+    mut query_followers: Query<(&mut Follow, Entity, Option<&StatusEffects>)>,
     mut transform_query: Query<&mut Transform>,
     time: Res<Time>,
 ) {
-    for (mut follow, entity) in query_followers.iter_mut() {
+    for (mut follow, entity, effects) in query_followers.iter_mut() {
         let pos: Vec2 = if let Ok(transform) = transform_query.get_mut(follow.target) {
             transform.translation.xy()
         } else {
@@ -66,10 +67,12 @@ pub fn follow_entity_system(
         if let Ok(mut transform) = transform_query.get_mut(entity) {
             //TODO: Check distance threshold (This was added because of Changed<>)
             if transform.translation.xy().distance(pos) > follow.threshold {
+                let speed_multiplier = effects.map_or(1., |effects| effects.speed_multiplier());
+
                 transform.translation = transform
                     .translation
                     .xy()
-                    .lerp(pos, follow.speed * time.delta_seconds())
+                    .lerp(pos, follow.speed * speed_multiplier * time.delta_seconds())
                     .extend(transform.translation.z);
 
                 follow.on_target = false;
@@ -88,13 +91,15 @@ pub fn follow_entity_system(
 pub struct Velocity(pub Vec2, pub bool);
 
 pub fn movement_system(
-    mut query_velocity: Query<(&Velocity, &mut Transform)>,
+    mut query_velocity: Query<(&Velocity, &mut Transform, Option<&StatusEffects>)>,
     tile_query: Query<(&TileStorage, &TilemapType, &TilemapSize, &TilemapGridSize)>,
     walkable_tiles_query: Query<&WalkableTile>,
     time: Res<Time>,
 ) {
-    for (velocity, mut transform) in query_velocity.iter_mut() {
-        let new_pos = transform.translation + velocity.0.extend(0.) * time.delta_seconds();
+    for (velocity, mut transform, effects) in query_velocity.iter_mut() {
+        let speed_multiplier = effects.map_or(1., |effects| effects.speed_multiplier());
+        let new_pos =
+            transform.translation + velocity.0.extend(0.) * speed_multiplier * time.delta_seconds();
 
         if !velocity.1 {
             //If not restricted to walkable tiles
@@ -1,30 +1,33 @@
 use std::usize;
 
-use bevy::prelude::MouseButton;
 use bevy::reflect::Reflect;
 use bevy::sprite::{SpriteBundle, TextureAtlas};
+use bevy::time::{Timer, TimerMode};
 use bevy::{
-    prelude::{default, Bundle, Component, KeyCode, Transform, Vec3},
+    prelude::{default, Bundle, Component, Transform, Vec3},
     utils::HashMap,
 };
 use bevy_rapier2d::prelude::{
     ActiveCollisionTypes, ActiveEvents, Collider, CollisionGroups, RigidBody,
 };
 use bevy_spritesheet_animation::prelude::SpritesheetAnimation;
-use leafwing_input_manager::{
-    prelude::{ActionState, InputMap},
-    InputManagerBundle,
-};
+use leafwing_input_manager::{prelude::ActionState, InputManagerBundle};
+
+use serde::{Deserialize, Serialize};
 
 use crate::animation::{Animations, DirectionalAnimations};
+use crate::controls::Controls;
+use crate::damage::{CriticalStrike, HitReaction, Resistances};
 use crate::effects::Shadow;
+use crate::heal::Lifesteal;
 use crate::manifest::player::PlayerData;
 use crate::sorting::{self, FeetOffset, YSort};
+use crate::weapon::Weapons;
 use crate::Progression;
 use crate::{
-    attack::Damageable,
+    attack::{ChargedAttack, Damageable},
     collision::BodyLayers,
-    controller::Controlled,
+    controller::{Aiming, ComboChain, Controlled, InputBuffer, ParryAbility, RangedAttack},
     movement::direction::Direction,
     state::State,
     stats::{Cooldown, Damage, Health, MovementSpeed, StatsBundle, XP},
@@ -57,6 +60,17 @@ pub struct PlayerBundle {
     stats: StatsBundle,
     progression: Progression,
     damageable: Damageable,
+    lifesteal: Lifesteal,
+    crit: CriticalStrike,
+    resistances: Resistances,
+    hit_reaction: HitReaction,
+    combo: ComboChain,
+    weapons: Weapons,
+    aim: Aiming,
+    input_buffer: InputBuffer,
+    secondary: RangedAttack,
+    heavy: ChargedAttack,
+    parry: ParryAbility,
     input: InputManagerBundle<PlayerActions>,
     ysort: YSort,
     feet_offset: FeetOffset,
@@ -64,44 +78,44 @@ pub struct PlayerBundle {
 }
 
 impl PlayerBundle {
-    pub fn new(data: &PlayerData) -> Self {
+    pub fn new(data: &PlayerData, controls: &Controls) -> Self {
         /* let mut player_animations = HashMap::new();
 
                 let mut idle_animations = HashMap::new();
-                idle_animations.insert(Direction::SOUTH, (0..7).collect());
-                idle_animations.insert(Direction::EAST, (10..17).collect());
-                idle_animations.insert(Direction::NORTH, (20..27).collect());
-                idle_animations.insert(Direction::WEST, (30..37).collect());
+                idle_animations.insert(Direction::South, (0..7).collect());
+                idle_animations.insert(Direction::East, (10..17).collect());
+                idle_animations.insert(Direction::North, (20..27).collect());
+                idle_animations.insert(Direction::West, (30..37).collect());
 
                 let mut walk_animations = HashMap::new();
-                walk_animations.insert(Direction::SOUTH, (40..47).collect());
-                walk_animations.insert(Direction::EAST, (50..57).collect());
-                walk_animations.insert(Direction::NORTH, (60..67).collect());
-                walk_animations.insert(Direction::WEST, (70..77).collect());
+                walk_animations.insert(Direction::South, (40..47).collect());
+                walk_animations.insert(Direction::East, (50..57).collect());
+                walk_animations.insert(Direction::North, (60..67).collect());
+                walk_animations.insert(Direction::West, (70..77).collect());
 
                 let mut dash_animations = HashMap::new();
-                dash_animations.insert(Direction::SOUTH, (80..87).collect());
-                dash_animations.insert(Direction::EAST, (90..97).collect());
-                dash_animations.insert(Direction::NORTH, (100..107).collect());
-                dash_animations.insert(Direction::WEST, (110..117).collect());
+                dash_animations.insert(Direction::South, (80..87).collect());
+                dash_animations.insert(Direction::East, (90..97).collect());
+                dash_animations.insert(Direction::North, (100..107).collect());
+                dash_animations.insert(Direction::West, (110..117).collect());
 
                 let mut attack_animations = HashMap::new();
-                attack_animations.insert(Direction::SOUTH, (120..125).collect());
-                attack_animations.insert(Direction::EAST, (130..135).collect());
-                attack_animations.insert(Direction::NORTH, (140..145).collect());
-                attack_animations.insert(Direction::WEST, (150..155).collect());
+                attack_animations.insert(Direction::South, (120..125).collect());
+                attack_animations.insert(Direction::East, (130..135).collect());
+                attack_animations.insert(Direction::North, (140..145).collect());
+                attack_animations.insert(Direction::West, (150..155).collect());
 
                 let mut attack_animations_1 = HashMap::new();
-                attack_animations_1.insert(Direction::SOUTH, (160..165).collect());
-                attack_animations_1.insert(Direction::EAST, (170..175).collect());
-                attack_animations_1.insert(Direction::NORTH, (180..185).collect());
-                attack_animations_1.insert(Direction::WEST, (190..195).collect());
+                attack_animations_1.insert(Direction::South, (160..165).collect());
+                attack_animations_1.insert(Direction::East, (170..175).collect());
+                attack_animations_1.insert(Direction::North, (180..185).collect());
+                attack_animations_1.insert(Direction::West, (190..195).collect());
 
                 let mut attack_animations_2 = HashMap::new();
-                attack_animations_2.insert(Direction::SOUTH, (200..209).collect());
-                attack_animations_2.insert(Direction::EAST, (210..219).collect());
-                attack_animations_2.insert(Direction::NORTH, (220..229).collect());
-                attack_animations_2.insert(Direction::WEST, (230..239).collect());
+                attack_animations_2.insert(Direction::South, (200..209).collect());
+                attack_animations_2.insert(Direction::East, (210..219).collect());
+                attack_animations_2.insert(Direction::North, (220..229).collect());
+                attack_animations_2.insert(Direction::West, (230..239).collect());
 
                 player_animations.insert(State::Idle, idle_animations);
                 player_animations.insert(State::Walking, walk_animations);
@@ -135,21 +149,17 @@ impl PlayerBundle {
             animations: data.animations.clone(),
             //TODO: Fix this to have a default animation or be insert later on
             animation: SpritesheetAnimation::from_id(
-                *data
-                    .animations
-                    .0
-                    .get(&State::Idle)
-                    .expect("No IDLE animation found for player")
-                    .get(&Direction::SOUTH)
-                    .expect("No SOUTH animation found for player"),
+                data.animations
+                    .get(&State::Idle, &Direction::South)
+                    .expect("No IDLE animation found for player"),
             ),
             collision_events: ActiveEvents::COLLISION_EVENTS,
             collision_types: ActiveCollisionTypes::all(),
             collision_groups: CollisionGroups::new(
                 BodyLayers::PLAYER,
-                BodyLayers::XP_LAYER | BodyLayers::ENEMY_ATTACK,
+                BodyLayers::XP_LAYER | BodyLayers::PICKUP_LAYER | BodyLayers::ENEMY_ATTACK,
             ),
-            direction: Direction::SOUTH,
+            direction: Direction::South,
             state: State::Idle,
             stats: StatsBundle {
                 health: Health::new(data.health),
@@ -158,55 +168,113 @@ impl PlayerBundle {
                 xp: XP::new(data.xp),
                 cooldown: Cooldown::new(data.cooldown),
             },
-            progression: Progression::new(data.base_xp, data.xp_multiplier),
+            progression: Progression::new(
+                data.base_xp,
+                data.xp_multiplier,
+                data.lifesteal_per_level.unwrap_or_default(),
+            ),
             level: Level::default(),
             revenge: Revenge {
                 amount: 0.,
-                decay: 4.5,
-                active_decay: 8.,
+                decay: data.revenge.decay,
+                active_decay: data.revenge.active_decay,
                 active: false,
-                total: 75.,
+                total: data.revenge.total,
+                damage_multiplier: data.revenge.damage_multiplier,
+                speed_multiplier: data.revenge.speed_multiplier,
+                manual: data.revenge.manual,
+                effects: data.revenge.effects.clone(),
             },
             damageable: Damageable,
+            lifesteal: Lifesteal(data.lifesteal.unwrap_or_default()),
+            crit: CriticalStrike {
+                chance: data.crit_chance.unwrap_or(0.05),
+                multiplier: data.crit_multiplier.unwrap_or(1.5),
+            },
+            resistances: Resistances(data.resistances.clone()),
+            combo: ComboChain::default(),
+            aim: Aiming::default(),
+            input_buffer: InputBuffer::new(data.input_buffer.unwrap_or(0.25)),
+            secondary: RangedAttack {
+                damage_multiplier: data.secondary.damage_multiplier,
+                velocity: data.secondary.velocity,
+                lifetime: data.secondary.lifetime,
+                size: data.secondary.size,
+                texture: data.secondary.texture.clone(),
+                atlas: data.secondary.atlas.clone(),
+                cooldown: ready_timer(data.secondary.cooldown),
+            },
+            heavy: data.heavy.clone(),
+            parry: ParryAbility {
+                animation: data.parry.animation,
+                window: data.parry.window,
+                recover: data.parry.recover,
+                stun: data.parry.stun,
+                revenge: data.parry.revenge,
+                cooldown: ready_timer(data.parry.cooldown),
+            },
+            weapons: Weapons {
+                slots: [Some(data.weapon.clone()), None],
+                active: 0,
+            },
+            hit_reaction: data.hit_reaction,
             input: InputManagerBundle::<PlayerActions> {
                 action_state: ActionState::default(),
-                input_map: Self::default_keybindings(),
+                input_map: controls.input_map(),
             },
             ysort: YSort(sorting::ENTITIES_LAYER),
             feet_offset: FeetOffset(feet_offset),
             shadow: Shadow,
         }
     }
+}
 
-    fn default_keybindings() -> InputMap<PlayerActions> {
-        //TODO: Check best keybindings
-        use PlayerActions::*;
-        let mut input_map = InputMap::default();
-
-        input_map
-            .insert(PlayerActions::MoveUp, KeyCode::KeyW)
-            .insert(MoveDown, KeyCode::KeyS)
-            .insert(MoveLeft, KeyCode::KeyA)
-            .insert(MoveRight, KeyCode::KeyD)
-            .insert(Attack, KeyCode::KeyJ)
-            .insert(Attack, MouseButton::Left)
-            .insert(Dash, KeyCode::Space);
-
-        input_map
-    }
+//Cooldown that starts finished, so the ability can be used right away
+fn ready_timer(seconds: f32) -> Timer {
+    let mut timer = Timer::from_seconds(seconds, TimerMode::Once);
+    timer.tick(timer.duration());
+    timer
 }
 
-#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect)]
+#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect, Serialize, Deserialize)]
 pub enum PlayerActions {
     MoveUp,
     MoveDown,
     MoveLeft,
     MoveRight,
     Attack,
+    SecondaryAttack,
+    HeavyAttack,
+    Parry,
+    ActivateRevenge,
     Dash,
+    SwapWeapon,
+    Interact,
+    Map,
+    #[actionlike(DualAxis)]
+    Move,
+    #[actionlike(DualAxis)]
+    Aim,
 }
 
 impl PlayerActions {
+    //Every action except moving and aiming with the sticks, which are analog only
+    pub const REBINDABLE: [Self; 13] = [
+        Self::MoveUp,
+        Self::MoveDown,
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::Attack,
+        Self::SecondaryAttack,
+        Self::HeavyAttack,
+        Self::Parry,
+        Self::ActivateRevenge,
+        Self::Dash,
+        Self::SwapWeapon,
+        Self::Interact,
+        Self::Map,
+    ];
+
     pub const DIRECTIONS: [Self; 4] = [
         Self::MoveUp,
         Self::MoveDown,
@@ -216,11 +284,31 @@ impl PlayerActions {
 
     pub fn direction(&self) -> Option<Direction> {
         match self {
-            PlayerActions::MoveUp => Some(Direction::NORTH),
-            PlayerActions::MoveDown => Some(Direction::SOUTH),
-            PlayerActions::MoveLeft => Some(Direction::WEST),
-            PlayerActions::MoveRight => Some(Direction::EAST),
+            PlayerActions::MoveUp => Some(Direction::North),
+            PlayerActions::MoveDown => Some(Direction::South),
+            PlayerActions::MoveLeft => Some(Direction::West),
+            PlayerActions::MoveRight => Some(Direction::East),
             _ => None,
         }
     }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlayerActions::MoveUp => "Move Up",
+            PlayerActions::MoveDown => "Move Down",
+            PlayerActions::MoveLeft => "Move Left",
+            PlayerActions::MoveRight => "Move Right",
+            PlayerActions::Attack => "Attack",
+            PlayerActions::SecondaryAttack => "Secondary Attack",
+            PlayerActions::HeavyAttack => "Heavy Attack",
+            PlayerActions::Parry => "Parry",
+            PlayerActions::ActivateRevenge => "Activate Revenge",
+            PlayerActions::Dash => "Dash",
+            PlayerActions::SwapWeapon => "Swap Weapon",
+            PlayerActions::Interact => "Interact",
+            PlayerActions::Map => "Map",
+            PlayerActions::Move => "Move",
+            PlayerActions::Aim => "Aim",
+        }
+    }
 }
@@ -0,0 +1,316 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+use bevy_ecs_tilemap::{
+    prelude::{TilemapGridSize, TilemapSize, TilemapType},
+    tiles::TileStorage,
+};
+use bevy_rapier2d::prelude::CollisionGroups;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    attack::{Breakable, Damageable, Owner, ProjectileBundle},
+    death::Dying,
+    manifest::AttackData,
+    map::walkable::{tile_at, WalkableTile},
+    movement::movement::Velocity,
+    stats::Damage,
+    status::{AttackEffects, Stunned},
+    GameState, InRun,
+};
+
+pub struct ProjectilePlugin;
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (burst_emitters, homing, acceleration, bounce).run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+//How a ranged attack fires its projectiles, the default is a single straight one
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
+#[serde(default)]
+pub struct ProjectilePattern {
+    pub count: u32,        // Projectiles fired per shot
+    pub spread: f32,       // Degrees between the first and last projectile, 360 for a ring
+    pub bursts: u32,       // Shots fired per attack
+    pub interval: f32,     // Seconds between shots
+    pub spiral: f32,       // Degrees each shot is rotated from the previous one
+    pub homing: f32,       // Degrees per second the projectiles turn towards their target
+    pub pierce: u32,       // Extra targets hit before breaking
+    pub bounces: u32,      // Times it bounces off non-walkable tiles, breaks on the next one
+    pub acceleration: f32, // Speed gained per second, negative slows it down
+}
+
+impl Default for ProjectilePattern {
+    fn default() -> Self {
+        Self {
+            count: 1,
+            spread: 0.,
+            bursts: 1,
+            interval: 0.,
+            spiral: 0.,
+            homing: 0.,
+            pierce: 0,
+            bounces: 0,
+            acceleration: 0.,
+        }
+    }
+}
+
+impl ProjectilePattern {
+    //Seconds from the first shot to the last one
+    pub fn burst_time(&self) -> f32 {
+        self.bursts.saturating_sub(1) as f32 * self.interval
+    }
+
+    //Angle of each projectile of a shot relative to the aim, centered on it
+    fn angles(&self) -> impl Iterator<Item = f32> {
+        let count = self.count.max(1);
+        let spread = self.spread.to_radians();
+
+        let step = if count == 1 {
+            0.
+        } else if self.spread >= 360. {
+            spread / count as f32
+        } else {
+            spread / (count - 1) as f32
+        };
+
+        let start = -step * (count - 1) as f32 / 2.;
+
+        (0..count).map(move |i| start + step * i as f32)
+    }
+}
+
+//Fires the rest of the shots of a pattern from the attacker's position
+#[derive(Component)]
+pub struct BurstEmitter {
+    pub data: AttackData,
+    pub damage: Damage,
+    pub direction: Vec2,
+    pub attacker: Entity,
+    pub shot: u32,
+    pub timer: Timer,
+}
+
+//Radians per second it turns towards the player
+#[derive(Component)]
+pub struct Homing(pub f32);
+
+#[derive(Component)]
+pub struct Acceleration(pub f32);
+
+//Bounces left before breaking on a non-walkable tile
+#[derive(Component)]
+pub struct Bounces(pub u32);
+
+//Spawns every projectile of a single shot, the shot index is used for the spiral rotation
+pub fn fire_shot(
+    commands: &mut Commands,
+    data: &AttackData,
+    damage: Damage,
+    direction: Vec2,
+    position: Vec3,
+    attacker: Entity,
+    shot: u32,
+) {
+    let AttackData::Ranged {
+        size,
+        duration,
+        velocity,
+        texture,
+        atlas,
+        damage_kind,
+        effects,
+        feedback,
+        pattern,
+    } = data
+    else {
+        return;
+    };
+
+    let aim = Vec2::from_angle(pattern.spiral.to_radians() * shot as f32).rotate(direction);
+
+    for angle in pattern.angles() {
+        let direction = Vec2::from_angle(angle).rotate(aim);
+
+        let mut projectile = commands.spawn((
+            ProjectileBundle::new(
+                texture.clone(),
+                atlas.clone(),
+                position,
+                f32::atan2(direction.y, direction.x),
+                *size / 2.,
+                *duration,
+                damage.with_kind(*damage_kind),
+                false,
+                Velocity(direction * *velocity, false),
+            ),
+            AttackEffects(effects.clone()),
+            *feedback,
+            Owner(attacker),
+            StateScoped(InRun),
+        ));
+
+        if pattern.pierce > 0 {
+            projectile.insert(Breakable(pattern.pierce + 1));
+        }
+
+        if pattern.homing > 0. {
+            projectile.insert(Homing(pattern.homing.to_radians()));
+        }
+
+        if pattern.acceleration != 0. {
+            projectile.insert(Acceleration(pattern.acceleration));
+        }
+
+        if pattern.bounces > 0 {
+            projectile.insert(Bounces(pattern.bounces));
+        }
+    }
+}
+
+fn burst_emitters(
+    mut query: Query<(&mut BurstEmitter, Entity)>,
+    attackers: Query<(&Transform, Has<Stunned>, Has<Dying>)>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    for (mut emitter, entity) in query.iter_mut() {
+        emitter.timer.tick(time.delta());
+
+        //Short intervals can finish more than once in a slow frame
+        let shots = emitter.timer.times_finished_this_tick();
+
+        if shots == 0 {
+            continue;
+        }
+
+        //The rest of the burst is cancelled if the attacker dies or gets stunned
+        let Ok((transform, false, false)) = attackers.get(emitter.attacker) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+
+        let AttackData::Ranged { pattern, .. } = &emitter.data else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+
+        let bursts = pattern.bursts;
+
+        for _ in 0..shots {
+            fire_shot(
+                &mut commands,
+                &emitter.data,
+                emitter.damage,
+                emitter.direction,
+                transform.translation,
+                emitter.attacker,
+                emitter.shot,
+            );
+
+            emitter.shot += 1;
+
+            if emitter.shot >= bursts {
+                commands.entity(entity).despawn();
+                break;
+            }
+        }
+    }
+}
+
+//Projectiles turn towards the closest entity their collision groups can hit
+fn homing(
+    mut query: Query<(&Homing, &CollisionGroups, &mut Velocity, &mut Transform)>,
+    targets: Query<
+        (&Transform, &CollisionGroups),
+        (With<Damageable>, Without<Dying>, Without<Homing>),
+    >,
+    time: Res<Time>,
+) {
+    for (homing, groups, mut velocity, mut transform) in query.iter_mut() {
+        let position = transform.translation.xy();
+
+        let Some(to_target) = targets
+            .iter()
+            .filter(|(_, target_groups)| groups.filters.intersects(target_groups.memberships))
+            .map(|(target, _)| target.translation.xy() - position)
+            .min_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+        else {
+            continue;
+        };
+
+        if to_target == Vec2::ZERO || velocity.0 == Vec2::ZERO {
+            continue;
+        }
+
+        let max_turn = homing.0 * time.delta_seconds();
+        let turn = velocity
+            .0
+            .angle_between(to_target)
+            .clamp(-max_turn, max_turn);
+
+        velocity.0 = Vec2::from_angle(turn).rotate(velocity.0);
+        transform.rotation = Quat::from_rotation_z(f32::atan2(velocity.0.y, velocity.0.x));
+    }
+}
+
+fn acceleration(mut query: Query<(&Acceleration, &mut Velocity)>, time: Res<Time>) {
+    //Slowed down projectiles stop instead of going backwards
+    const MIN_SPEED: f32 = 1.;
+
+    for (acceleration, mut velocity) in query.iter_mut() {
+        let speed = (velocity.0.length() + acceleration.0 * time.delta_seconds()).max(MIN_SPEED);
+        velocity.0 = velocity.0.normalize_or_zero() * speed;
+    }
+}
+
+fn bounce(
+    mut query: Query<(&mut Bounces, &mut Velocity, &mut Transform, &mut Breakable)>,
+    tile_query: Query<(&TileStorage, &TilemapType, &TilemapSize, &TilemapGridSize)>,
+    walkable_tiles_query: Query<&WalkableTile>,
+    time: Res<Time>,
+) {
+    let Some((tile_storage, tilemap_type, map_size, grid_size)) = tile_query.iter().next() else {
+        return;
+    };
+
+    let walkable = |pos: Vec2| {
+        tile_at(pos, map_size, grid_size, tilemap_type)
+            .and_then(|tile_pos| tile_storage.get(&tile_pos))
+            .is_some_and(|tile| walkable_tiles_query.get(tile).is_ok())
+    };
+
+    for (mut bounces, mut velocity, mut transform, mut breakable) in query.iter_mut() {
+        let pos = transform.translation.xy();
+        let step = velocity.0 * time.delta_seconds();
+
+        if walkable(pos + step) {
+            continue;
+        }
+
+        if bounces.0 == 0 {
+            breakable.0 = 0;
+            continue;
+        }
+
+        bounces.0 -= 1;
+
+        //Only flip the axis that hits the wall, corners flip both
+        let flip_x = !walkable(pos + Vec2::new(step.x, 0.));
+        let flip_y = !walkable(pos + Vec2::new(0., step.y));
+
+        if flip_x == flip_y {
+            velocity.0 = -velocity.0;
+        } else if flip_x {
+            velocity.0.x = -velocity.0.x;
+        } else {
+            velocity.0.y = -velocity.0.y;
+        }
+
+        transform.rotation = Quat::from_rotation_z(f32::atan2(velocity.0.y, velocity.0.x));
+    }
+}
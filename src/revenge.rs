@@ -0,0 +1,385 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use bevy_rapier2d::prelude::CollisionGroups;
+use leafwing_input_manager::prelude::ActionState;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    attack::{Breakable, ComboStep, EntitiesHit, Knockback, MeleeAttackBundle, Owner},
+    collision::BodyLayers,
+    death::Dying,
+    feedback::HitFlash,
+    game_states::loading::GameAssets,
+    heal::Lifesteal,
+    movement::{direction::Direction, movement::Velocity},
+    player::{Player, PlayerActions},
+    projectile::Homing,
+    sorting::FeetOffset,
+    state::State,
+    stats::{Damage, Revenge},
+    GameState,
+};
+
+pub struct RevengePlugin;
+
+impl Plugin for RevengePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<RevengeEvent>().add_systems(
+            Update,
+            (
+                revenge_mode,
+                revenge_started,
+                reflect_projectiles,
+                dash_area,
+                revenge_tint,
+                animate_aura,
+                draw_vignette,
+            )
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+//What the player gets while in revenge mode, on top of the damage and speed multipliers
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(tag = "type")]
+pub enum RevengeEffect {
+    //Added to the player's lifesteal
+    Lifesteal {
+        amount: f32,
+    },
+    //Enemy projectiles this close are sent back
+    ReflectProjectiles {
+        radius: f32,
+    },
+    //Added at the end of the weapon's combo
+    ExtraComboStep {
+        step: ComboStep,
+    },
+    //Damages everything the player dashes through
+    DashArea {
+        size: Vec2,
+        damage_multiplier: f32,
+        duration: f32,
+    },
+}
+
+//Sent when revenge mode starts or ends
+#[derive(Event)]
+pub struct RevengeEvent {
+    pub entity: Entity,
+    pub active: bool,
+}
+
+#[derive(Component)]
+pub struct RevengeAura;
+
+//Sprite color before revenge mode, restored once it ends
+#[derive(Component)]
+pub struct RevengeTint(Color);
+
+const REVENGE_TINT: Color = Color::srgb(1., 0.45, 0.45);
+
+pub fn revenge_mode(
+    mut query: Query<(&mut Revenge, Option<&ActionState<PlayerActions>>, Entity)>,
+    mut writer: EventWriter<RevengeEvent>,
+    time: Res<Time>,
+) {
+    for (mut revenge, action_state, entity) in query.iter_mut() {
+        if revenge.active {
+            if revenge.amount <= 0. {
+                revenge.active = false;
+                writer.send(RevengeEvent {
+                    entity,
+                    active: false,
+                });
+            }
+        } else if revenge.amount >= revenge.total
+            && (!revenge.manual
+                || action_state
+                    .is_some_and(|state| state.just_pressed(&PlayerActions::ActivateRevenge)))
+        {
+            revenge.active = true;
+            writer.send(RevengeEvent {
+                entity,
+                active: true,
+            });
+        }
+
+        if revenge.amount > revenge.total {
+            revenge.amount = revenge.total;
+        }
+
+        //A full bar waits for the player to trigger it
+        if revenge.manual && revenge.is_ready() {
+            continue;
+        }
+
+        let decay = revenge.decay() * time.delta_seconds();
+
+        if revenge.amount > decay {
+            revenge.amount -= decay;
+        } else {
+            revenge.amount = 0.;
+        }
+    }
+}
+
+//Applies the lasting effects and the aura when revenge mode starts, and undoes them when it ends
+fn revenge_started(
+    mut events: EventReader<RevengeEvent>,
+    mut query: Query<(
+        &Revenge,
+        Option<&mut Lifesteal>,
+        Option<&mut Sprite>,
+        Option<&mut HitFlash>,
+        Option<&RevengeTint>,
+        Option<&FeetOffset>,
+    )>,
+    auras: Query<(Entity, &Parent), With<RevengeAura>>,
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+) {
+    for event in events.read() {
+        let Ok((revenge, lifesteal, sprite, flash, tint, offset)) = query.get_mut(event.entity)
+        else {
+            continue;
+        };
+
+        if let Some(mut lifesteal) = lifesteal {
+            let amount = revenge
+                .effects
+                .iter()
+                .filter_map(|effect| match effect {
+                    RevengeEffect::Lifesteal { amount } => Some(*amount),
+                    _ => None,
+                })
+                .sum::<f32>();
+
+            if event.active {
+                lifesteal.0 += amount;
+            } else {
+                lifesteal.0 -= amount;
+            }
+        }
+
+        if event.active {
+            let offset = offset.map_or(0., |offset| offset.0);
+
+            //A running hit flash already replaced the color with its own
+            if let Some(sprite) = sprite {
+                let color = flash.and_then(|flash| flash.color).unwrap_or(sprite.color);
+                commands.entity(event.entity).insert(RevengeTint(color));
+            }
+
+            commands.entity(event.entity).with_children(|parent| {
+                parent.spawn((
+                    SpriteBundle {
+                        texture: game_assets.shadow_texture.clone(),
+                        sprite: Sprite {
+                            color: Color::srgba(1., 0.1, 0.1, 0.6),
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(0., -offset, -0.4),
+                        ..default()
+                    },
+                    RevengeAura,
+                ));
+            });
+        } else {
+            for (aura, parent) in auras.iter() {
+                if parent.get() == event.entity {
+                    commands.entity(aura).despawn_recursive();
+                }
+            }
+
+            if let (Some(mut sprite), Some(tint)) = (sprite, tint) {
+                if let Some(mut flash) = flash {
+                    flash.color = Some(tint.0);
+                } else {
+                    let alpha = sprite.color.alpha();
+                    sprite.color = tint.0.with_alpha(alpha);
+                }
+
+                commands.entity(event.entity).remove::<RevengeTint>();
+            }
+        }
+    }
+}
+
+//Turns enemy projectiles around, they count as the player's attacks afterwards
+fn reflect_projectiles(
+    player_query: Query<(&Revenge, &Transform, Entity), With<Player>>,
+    mut projectiles: Query<
+        (
+            &mut Velocity,
+            &mut Transform,
+            &mut CollisionGroups,
+            &mut EntitiesHit,
+            Option<&mut Owner>,
+            Entity,
+        ),
+        (With<Breakable>, Without<Player>),
+    >,
+    mut commands: Commands,
+) {
+    let Ok((revenge, player_transform, player)) = player_query.get_single() else {
+        return;
+    };
+
+    if !revenge.active {
+        return;
+    }
+
+    let Some(radius) = revenge
+        .effects
+        .iter()
+        .filter_map(|effect| match effect {
+            RevengeEffect::ReflectProjectiles { radius } => Some(*radius),
+            _ => None,
+        })
+        .reduce(f32::max)
+    else {
+        return;
+    };
+
+    for (mut velocity, mut transform, mut groups, mut entities_hit, owner, entity) in
+        projectiles.iter_mut()
+    {
+        if groups.memberships != BodyLayers::ENEMY_ATTACK
+            || transform
+                .translation
+                .truncate()
+                .distance(player_transform.translation.truncate())
+                > radius
+        {
+            continue;
+        }
+
+        velocity.0 = -velocity.0;
+        transform.rotate_z(PI);
+        //Reflected projectiles go through everything in their way
+        commands.entity(entity).remove::<(Homing, Breakable)>();
+        *groups = CollisionGroups::new(BodyLayers::PLAYER_ATTACK, BodyLayers::ENEMY);
+        entities_hit.0.clear();
+
+        match owner {
+            Some(mut owner) => owner.0 = player,
+            None => {
+                commands.entity(entity).insert(Owner(player));
+            }
+        }
+    }
+}
+
+fn dash_area(
+    query: Query<(&State, &Revenge, &Damage, &Direction, Entity), (With<Player>, Changed<State>)>,
+    mut commands: Commands,
+) {
+    let Ok((state, revenge, damage, direction, entity)) = query.get_single() else {
+        return;
+    };
+
+    if !revenge.active || !state.equals(State::Dashing) {
+        return;
+    }
+
+    for effect in revenge.effects.iter() {
+        let RevengeEffect::DashArea {
+            size,
+            damage_multiplier,
+            duration,
+        } = effect
+        else {
+            continue;
+        };
+
+        let damage = Damage::new(
+            (damage.amount as f32 * revenge.damage_scale() * damage_multiplier).round() as u32,
+        )
+        .with_kind(damage.kind);
+
+        //Attached to the player so it follows the dash
+        commands.entity(entity).with_children(|children| {
+            children.spawn(MeleeAttackBundle::new(
+                Vec3::new(0., 0., 10.),
+                *size,
+                *duration,
+                damage,
+                Knockback {
+                    force: 8.,
+                    direction: *direction,
+                },
+                true,
+            ));
+        });
+    }
+}
+
+//Hit flashes take over the color while they last
+fn revenge_tint(
+    mut query: Query<(&Revenge, &mut Sprite), (With<Player>, Without<HitFlash>, Without<Dying>)>,
+) {
+    for (revenge, mut sprite) in query.iter_mut() {
+        if revenge.active {
+            let alpha = sprite.color.alpha();
+            sprite.color = REVENGE_TINT.with_alpha(alpha);
+        }
+    }
+}
+
+fn animate_aura(mut query: Query<&mut Transform, With<RevengeAura>>, time: Res<Time>) {
+    let scale = 1.6 + (time.elapsed_seconds() * 6.).sin() * 0.2;
+
+    for mut transform in query.iter_mut() {
+        transform.scale = Vec3::new(scale, scale, 1.);
+    }
+}
+
+fn draw_vignette(
+    query: Query<&Revenge, With<Player>>,
+    mut contexts: EguiContexts,
+    windows: Query<&Window>,
+    time: Res<Time>,
+) {
+    const BANDS: u32 = 10;
+    const BAND_WIDTH: f32 = 6.;
+
+    let Ok(revenge) = query.get_single() else {
+        return;
+    };
+
+    if !revenge.active {
+        return;
+    }
+
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+
+    let painter = contexts.ctx_mut().layer_painter(egui::LayerId::new(
+        egui::Order::Background,
+        egui::Id::new("revenge_vignette"),
+    ));
+    let pulse = 0.75 + (time.elapsed_seconds() * 4.).sin() * 0.25;
+
+    //Stacked outlines that fade towards the center of the screen
+    for band in 0..BANDS {
+        let inset = band as f32 * BAND_WIDTH + BAND_WIDTH / 2.;
+        let alpha = (1. - band as f32 / BANDS as f32) * 90. * pulse;
+
+        painter.rect_stroke(
+            egui::Rect::from_min_max(
+                egui::Pos2::new(inset, inset),
+                egui::Pos2::new(window.width() - inset, window.height() - inset),
+            ),
+            0.,
+            egui::Stroke::new(
+                BAND_WIDTH,
+                egui::Color32::from_rgba_unmultiplied(150, 0, 0, alpha as u8),
+            ),
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use bevy::{
+    audio::{GlobalVolume, Volume},
+    prelude::*,
+    window::{PresentMode, PrimaryWindow, WindowMode},
+};
+use bevy_persistent::prelude::*;
+use bevy_rapier2d::render::DebugRenderContext;
+use serde::{Deserialize, Serialize};
+
+use crate::movement::movement::Follow;
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            apply_settings.run_if(resource_exists_and_changed::<Persistent<Settings>>),
+        );
+    }
+}
+
+//Orthographic scale at 1x zoom
+const BASE_CAMERA_SCALE: f32 = 0.25;
+
+pub const RESOLUTIONS: [(u32, u32); 5] = [
+    (1280, 720),
+    (1366, 768),
+    (1600, 900),
+    (1920, 1080),
+    (2560, 1440),
+];
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DisplayMode {
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+impl DisplayMode {
+    pub const ALL: [Self; 3] = [Self::Windowed, Self::Borderless, Self::Fullscreen];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DisplayMode::Windowed => "Windowed",
+            DisplayMode::Borderless => "Borderless",
+            DisplayMode::Fullscreen => "Fullscreen",
+        }
+    }
+}
+
+//Player settings, saved between runs and applied as soon as they change
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Settings {
+    pub display_mode: DisplayMode,
+    pub resolution: (u32, u32), // Only used when windowed
+    pub vsync: bool,
+    pub zoom: f32,
+    pub shake: f32, // Multiplier for the screen shake, 0 turns it off
+    pub damage_numbers: bool,
+    pub debug_overlay: bool, // Collider outlines from rapier
+    pub master_volume: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            display_mode: DisplayMode::Windowed,
+            resolution: (1280, 720),
+            vsync: true,
+            zoom: 1.,
+            shake: 1.,
+            damage_numbers: true,
+            debug_overlay: false,
+            master_volume: 1.,
+        }
+    }
+}
+
+impl Settings {
+    pub fn camera_scale(&self) -> f32 {
+        BASE_CAMERA_SCALE / self.zoom
+    }
+}
+
+fn apply_settings(
+    settings: Res<Persistent<Settings>>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    mut cameras: Query<&mut OrthographicProjection, With<Follow>>,
+    mut debug_render: ResMut<DebugRenderContext>,
+    mut global_volume: ResMut<GlobalVolume>,
+    mut applied: Local<Option<Settings>>,
+) {
+    if let Ok(mut window) = windows.get_single_mut() {
+        window.mode = match settings.display_mode {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            DisplayMode::Borderless => WindowMode::BorderlessFullscreen,
+            DisplayMode::Fullscreen => WindowMode::Fullscreen,
+        };
+
+        window.present_mode = match settings.vsync {
+            true => PresentMode::AutoVsync,
+            false => PresentMode::AutoNoVsync,
+        };
+
+        //Setting it again would undo any resize done by dragging the window
+        let resized = applied.as_ref().is_none_or(|applied| {
+            applied.resolution != settings.resolution
+                || applied.display_mode != settings.display_mode
+        });
+
+        if resized && settings.display_mode == DisplayMode::Windowed {
+            let (width, height) = settings.resolution;
+            window.resolution.set(width as f32, height as f32);
+        }
+    }
+
+    *applied = Some(settings.get().clone());
+
+    for mut projection in cameras.iter_mut() {
+        projection.scale = settings.camera_scale();
+    }
+
+    debug_render.enabled = settings.debug_overlay;
+    global_volume.volume = Volume::new(settings.master_volume);
+}
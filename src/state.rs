@@ -7,7 +7,7 @@ pub enum State {
     Walking,
     Attacking(u32), //Index of the attack in a combo
     Dashing,
-    _Dying,
+    Dying,
 }
 
 impl State {
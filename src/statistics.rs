@@ -4,7 +4,9 @@ use bevy::utils::Duration;
 use bevy_persistent::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::floor::EnemyKilledEvent;
 use crate::player::Player;
+use crate::stats::Revenge;
 
 #[derive(Resource, Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Statistics {
@@ -44,3 +46,31 @@ pub fn statistics(
         statistics.play_time += time.delta().as_secs_f32();
     }
 }
+
+pub fn revenge_statistics(
+    mut statistics: ResMut<Persistent<Statistics>>,
+    query: Query<&Revenge, With<Player>>,
+    time: Res<Time>,
+) {
+    for revenge in query.iter() {
+        if revenge.active {
+            statistics.revenge_time += time.delta().as_secs_f32();
+        }
+    }
+}
+
+//Only kills where the player dealt the last hit are counted
+pub fn kill_statistics(
+    mut statistics: ResMut<Persistent<Statistics>>,
+    mut events: EventReader<EnemyKilledEvent>,
+    player_query: Query<(), With<Player>>,
+) {
+    statistics.kills += events
+        .read()
+        .filter(|event| {
+            event
+                .killer
+                .is_some_and(|killer| player_query.contains(killer))
+        })
+        .count() as u32;
+}
@@ -1,21 +1,22 @@
 use std::time::Duration;
 
-use bevy::prelude::Time;
 use bevy::{
-    math::Vec3Swizzles,
     prelude::{
-        Bundle, Commands, Component, DespawnRecursiveExt, Entity, EventReader, EventWriter, Handle,
-        Image, Query, Res, Transform, Vec2, Vec3, With,
+        Bundle, Commands, Component, Entity, EventReader, EventWriter, Handle, Image, Query, Res,
+        StateScoped, Transform, Vec2, Vec3, With,
     },
     sprite::SpriteBundle,
     time::Timer,
 };
 use bevy_rapier2d::prelude::{ActiveCollisionTypes, ActiveEvents, Collider, CollisionGroups};
 
+use crate::attack::ComboStep;
+use crate::damage::DamageKind;
 use crate::game_states::loading::GameAssets;
+use crate::heal::{HealEvent, Lifesteal};
 use crate::{
-    collision::BodyLayers, enemy::Enemy, floor::EnemyKilledEvent, movement::movement::Follow,
-    player::Player,
+    collision::BodyLayers, floor::EnemyKilledEvent, movement::movement::Follow, player::Player,
+    revenge::RevengeEffect, InRun,
 };
 
 #[derive(Component)]
@@ -36,16 +37,32 @@ impl Health {
             self.current = 0;
         }
     }
+
+    //Returns the amount that was actually healed
+    pub fn heal(&mut self, amount: u32) -> u32 {
+        let healed = amount.min(self.max.saturating_sub(self.current));
+        self.current += healed;
+        healed
+    }
 }
 
 #[derive(Component, Clone, Copy)]
 pub struct Damage {
     pub amount: u32,
+    pub kind: DamageKind,
 }
 
 impl Damage {
     pub fn new(amount: u32) -> Self {
-        Self { amount }
+        Self {
+            amount,
+            kind: DamageKind::Physical,
+        }
+    }
+
+    pub fn with_kind(mut self, kind: DamageKind) -> Self {
+        self.kind = kind;
+        self
     }
 }
 
@@ -79,13 +96,15 @@ impl XP {
 pub struct Progression {
     pub base_xp: u32,
     pub multiplier: f32,
+    pub lifesteal: f32, // Lifesteal gained on every level up
 }
 
 impl Progression {
-    pub fn new(base_xp: u32, multiplier: f32) -> Self {
+    pub fn new(base_xp: u32, multiplier: f32, lifesteal: f32) -> Self {
         Self {
             base_xp,
             multiplier,
+            lifesteal,
         }
     }
 
@@ -112,6 +131,10 @@ pub struct Revenge {
     pub active_decay: f32,
     pub active: bool,
     pub total: f32,
+    pub damage_multiplier: f32,
+    pub speed_multiplier: f32,
+    pub manual: bool, // Waits for the player to activate it once full
+    pub effects: Vec<RevengeEffect>,
 }
 
 impl Revenge {
@@ -121,6 +144,39 @@ impl Revenge {
             false => self.decay,
         }
     }
+
+    pub fn is_ready(&self) -> bool {
+        !self.active && self.amount >= self.total
+    }
+
+    //Applied when the damage and speed are used, the stats themselves stay the same
+    pub fn damage_scale(&self) -> f32 {
+        if self.active {
+            self.damage_multiplier
+        } else {
+            1.
+        }
+    }
+
+    pub fn speed_scale(&self) -> f32 {
+        if self.active {
+            self.speed_multiplier
+        } else {
+            1.
+        }
+    }
+
+    //Combo step added to the end of the chain while active
+    pub fn extra_step(&self) -> Option<&ComboStep> {
+        if !self.active {
+            return None;
+        }
+
+        self.effects.iter().find_map(|effect| match effect {
+            RevengeEffect::ExtraComboStep { step } => Some(step),
+            _ => None,
+        })
+    }
 }
 
 #[derive(Component)]
@@ -157,22 +213,6 @@ pub struct StatsBundle {
     pub cooldown: Cooldown,
 }
 
-pub fn death_system(
-    mut commands: Commands,
-    query: Query<(Entity, &Health, Option<&Enemy>)>,
-    mut enemy_kill_writer: EventWriter<EnemyKilledEvent>,
-) {
-    for (entity, health, enemy) in query.iter() {
-        if health.current == 0 {
-            if enemy.is_some() {
-                enemy_kill_writer.send(EnemyKilledEvent(entity));
-            } else {
-                commands.entity(entity).despawn_recursive();
-            }
-        }
-    }
-}
-
 //TODO: Move this to a separate file
 #[derive(Component)]
 pub struct Drop;
@@ -213,6 +253,7 @@ impl XPDropBundle {
                 collision_types: ActiveCollisionTypes::all(),
                 collision_groups: CollisionGroups::new(BodyLayers::XP_LAYER, BodyLayers::PLAYER),
             })
+            .insert(StateScoped(InRun))
             .id()
     }
 }
@@ -220,21 +261,18 @@ impl XPDropBundle {
 pub fn drop_xp_system(
     mut commands: Commands,
     mut enemy_kill_reader: EventReader<EnemyKilledEvent>,
-    query: Query<(&Transform, &XP), With<Enemy>>,
     game_assets: Res<GameAssets>,
     player_query: Query<Entity, With<Player>>,
 ) {
     if let Ok(player) = player_query.get_single() {
         for event in enemy_kill_reader.read() {
-            if let Ok((transform, xp)) = query.get(event.0) {
-                XPDropBundle::spawn_enemy_drop(
-                    transform.translation.xy(),
-                    xp.amount,
-                    &mut commands,
-                    game_assets.xp_texture.clone(),
-                    player,
-                );
-            }
+            XPDropBundle::spawn_enemy_drop(
+                event.position,
+                event.xp,
+                &mut commands,
+                game_assets.xp_texture.clone(),
+                player,
+            );
         }
     }
 }
@@ -242,58 +280,39 @@ pub fn drop_xp_system(
 pub fn level_up(
     mut query: Query<
         (
+            Entity,
             &XP,
             &Progression,
             &mut Health,
             &mut Damage,
             &mut MovementSpeed,
             &mut Level,
+            Option<&mut Lifesteal>,
         ),
         With<Player>,
     >,
+    mut heal_writer: EventWriter<HealEvent>,
 ) {
-    for (xp, progression, mut health, mut damage, mut speed, mut level) in query.iter_mut() {
+    for (entity, xp, progression, mut health, mut damage, mut speed, mut level, lifesteal) in
+        query.iter_mut()
+    {
         if xp.amount >= progression.xp_to_level_up(level.level as i32) {
             //TODO: Add proper stats progression
             health.max += (health.max as f32 * progression.multiplier / 100.) as u32;
-            health.current = health.max;
+            heal_writer.send(HealEvent {
+                entity,
+                amount: health.max - health.current,
+            });
 
             speed.speed += (speed.speed as f32 * progression.multiplier / 100.) as u32;
 
             damage.amount += (damage.amount as f32 * progression.multiplier / 50.) as u32;
 
-            level.level += 1;
-        }
-    }
-}
-
-pub fn revenge_mode(
-    mut query: Query<(&mut Revenge, &mut Damage, &mut MovementSpeed)>,
-    time: Res<Time>,
-) {
-    for (mut revenge, mut damage, mut speed) in query.iter_mut() {
-        if revenge.active {
-            if revenge.amount <= 0. {
-                damage.amount = (damage.amount as f32 / 1.5) as u32;
-                speed.speed = (speed.speed as f32 / 1.5) as u32;
-                revenge.active = false;
+            if let Some(mut lifesteal) = lifesteal {
+                lifesteal.0 += progression.lifesteal;
             }
-        } else if revenge.amount >= revenge.total {
-            damage.amount = (damage.amount as f32 * 1.5) as u32;
-            speed.speed = (speed.speed as f32 * 1.5) as u32;
-            revenge.active = true;
-        }
 
-        let decay = revenge.decay() * time.delta_seconds();
-
-        if revenge.amount > revenge.total {
-            revenge.amount = revenge.total;
-        }
-
-        if revenge.amount > decay {
-            revenge.amount -= decay;
-        } else {
-            revenge.amount = 0.;
+            level.level += 1;
         }
     }
 }
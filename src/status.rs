@@ -0,0 +1,282 @@
+use bevy::{
+    prelude::{
+        in_state, Added, App, Color, Commands, Component, Entity, Event, EventReader, EventWriter,
+        Has, IntoSystemConfigs, Plugin, Query, Reflect, Res, Transform, Update, Vec2, With,
+    },
+    time::{Time, Timer, TimerMode},
+    utils::HashMap,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    attack::Damageable,
+    controller::Controlled,
+    damage::{DamageEvent, DamageKind, Invulnerable},
+    effects::floating_text,
+    feedback::HitFeedback,
+    game_states::loading::GameAssets,
+    movement::movement::{Follow, Velocity},
+    state::State,
+    stats::Damage,
+    GameState,
+};
+
+pub struct StatusPlugin;
+
+impl Plugin for StatusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ApplyStatusEvent>().add_systems(
+            Update,
+            (apply_status_effects, tick_status_effects, stun_started)
+                .chain()
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, Reflect)]
+pub enum StatusKind {
+    Burn,
+    Poison,
+    Slow,
+    Stun,
+}
+
+impl StatusKind {
+    pub fn color(&self) -> Color {
+        match self {
+            StatusKind::Burn => Color::srgb(1., 0.5, 0.1),
+            StatusKind::Poison => Color::srgb(0.5, 0.9, 0.2),
+            StatusKind::Slow => Color::srgb(0.4, 0.6, 1.),
+            StatusKind::Stun => Color::srgb(1., 0.9, 0.3),
+        }
+    }
+
+    pub fn damage_kind(&self) -> DamageKind {
+        match self {
+            StatusKind::Burn => DamageKind::Fire,
+            StatusKind::Poison => DamageKind::Poison,
+            _ => DamageKind::Physical,
+        }
+    }
+}
+
+//What happens when an effect is applied to an entity that already has it
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Default, Reflect)]
+pub enum Stacking {
+    #[default]
+    Refresh, // Restart the duration
+    Extend,    // Add the duration to the remaining one
+    Intensify, // Add a stack (up to max_stacks) and restart the duration
+    Ignore,    // Keep the current one
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Reflect)]
+pub struct StatusEffectData {
+    pub kind: StatusKind,
+    pub duration: f32,
+    pub tick_rate: Option<f32>, // Seconds between damage ticks
+    pub damage: Option<u32>,    // Damage per tick and stack
+    pub slow: Option<f32>,      // Fraction of the movement speed removed per stack
+    #[serde(default)]
+    pub stacking: Stacking,
+    pub max_stacks: Option<u32>,
+}
+
+//Effects applied by an attack to every entity it hits
+#[derive(Component, Clone)]
+pub struct AttackEffects(pub Vec<StatusEffectData>);
+
+#[derive(Event)]
+pub struct ApplyStatusEvent {
+    pub entity: Entity,
+    pub effect: StatusEffectData,
+}
+
+pub struct ActiveStatus {
+    pub data: StatusEffectData,
+    pub duration: Timer,
+    pub tick: Option<Timer>,
+    pub stacks: u32,
+}
+
+impl ActiveStatus {
+    fn new(data: StatusEffectData) -> Self {
+        Self {
+            data,
+            duration: Timer::from_seconds(data.duration, TimerMode::Once),
+            tick: data
+                .tick_rate
+                .map(|rate| Timer::from_seconds(rate, TimerMode::Repeating)),
+            stacks: 1,
+        }
+    }
+}
+
+#[derive(Component, Default)]
+pub struct StatusEffects(pub Vec<ActiveStatus>);
+
+impl StatusEffects {
+    //Returns true when the entity didn't have this kind of effect yet
+    pub fn apply(&mut self, data: StatusEffectData) -> bool {
+        let Some(active) = self.0.iter_mut().find(|s| s.data.kind == data.kind) else {
+            self.0.push(ActiveStatus::new(data));
+            return true;
+        };
+
+        match data.stacking {
+            Stacking::Refresh => active.duration.reset(),
+            Stacking::Extend => {
+                let remaining = active.duration.remaining_secs();
+                active.duration = Timer::from_seconds(remaining + data.duration, TimerMode::Once);
+            }
+            Stacking::Intensify => {
+                active.stacks = (active.stacks + 1).min(data.max_stacks.unwrap_or(u32::MAX));
+                active.duration.reset();
+            }
+            Stacking::Ignore => {}
+        }
+
+        false
+    }
+
+    pub fn is_stunned(&self) -> bool {
+        self.0.iter().any(|s| s.data.kind == StatusKind::Stun)
+    }
+
+    //Multiplier to apply to any movement of the entity
+    pub fn speed_multiplier(&self) -> f32 {
+        if self.is_stunned() {
+            return 0.;
+        }
+
+        self.0
+            .iter()
+            .filter_map(|s| s.data.slow.map(|slow| 1. - slow * s.stacks as f32))
+            .fold(1., f32::min)
+            .max(0.)
+    }
+}
+
+//Marker for entities that can't act, used to pause controllers and AI
+#[derive(Component)]
+pub struct Stunned;
+
+fn apply_status_effects(
+    mut events: EventReader<ApplyStatusEvent>,
+    mut query: Query<(Option<&mut StatusEffects>, &Transform), With<Damageable>>,
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+) {
+    let mut new_effects: HashMap<Entity, StatusEffects> = HashMap::new();
+
+    for event in events.read() {
+        let Ok((effects, transform)) = query.get_mut(event.entity) else {
+            continue;
+        };
+
+        let added = match effects {
+            Some(mut effects) => effects.apply(event.effect),
+            None => new_effects
+                .entry(event.entity)
+                .or_default()
+                .apply(event.effect),
+        };
+
+        //Name of the effect in its color when it starts
+        if added {
+            commands.spawn(floating_text(
+                game_assets.font.clone(),
+                transform.translation.truncate() + Vec2::new(0., 12.),
+                format!("{:?}", event.effect.kind),
+                event.effect.kind.color(),
+                10.,
+            ));
+        }
+    }
+
+    for (entity, effects) in new_effects {
+        if let Some(mut ec) = commands.get_entity(entity) {
+            ec.try_insert(effects);
+        }
+    }
+}
+
+fn tick_status_effects(
+    mut query: Query<(
+        Entity,
+        &mut StatusEffects,
+        Has<Stunned>,
+        Has<Invulnerable>,
+        Option<&State>,
+    )>,
+    mut damage_writer: EventWriter<DamageEvent>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    for (entity, mut effects, was_stunned, invulnerable, state) in query.iter_mut() {
+        //Ticks keep counting during i-frames, their damage is just not dealt
+        let immune = invulnerable || state.is_some_and(|state| state.equals(State::Dashing));
+
+        for status in effects.0.iter_mut() {
+            status.duration.tick(time.delta());
+
+            let (Some(tick), Some(damage)) = (&mut status.tick, status.data.damage) else {
+                continue;
+            };
+
+            tick.tick(time.delta());
+
+            let amount = damage * status.stacks * tick.times_finished_this_tick();
+            if amount == 0 || immune {
+                continue;
+            }
+
+            damage_writer.send(DamageEvent {
+                attacker: None,
+                victim: entity,
+                damage: Damage::new(amount).with_kind(status.data.kind.damage_kind()),
+                feedback: HitFeedback::TICK,
+                knockback: None,
+            });
+        }
+
+        effects.0.retain(|s| !s.duration.finished());
+
+        let stunned = effects.is_stunned();
+        let mut ec = commands.entity(entity);
+
+        if stunned && !was_stunned {
+            ec.try_insert(Stunned);
+        } else if !stunned && was_stunned {
+            ec.remove::<Stunned>();
+        }
+
+        if effects.0.is_empty() {
+            ec.remove::<StatusEffects>();
+        }
+    }
+}
+
+//Stops whatever the entity was doing when it gets stunned
+fn stun_started(
+    mut query: Query<(Entity, Option<&mut State>, Option<&mut Controlled>), Added<Stunned>>,
+    mut commands: Commands,
+) {
+    for (entity, state, controlled) in query.iter_mut() {
+        commands
+            .entity(entity)
+            .remove::<Velocity>()
+            .remove::<Follow>();
+
+        if let Some(mut state) = state {
+            if state.equals(State::Walking) {
+                state.set(State::Idle);
+            }
+        }
+
+        if let Some(mut controlled) = controlled {
+            controlled.move_to = None;
+        }
+    }
+}
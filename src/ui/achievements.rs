@@ -0,0 +1,105 @@
+use bevy::{
+    prelude::{Commands, Entity, Query, Res, ResMut},
+    time::Time,
+};
+use bevy_egui::{
+    egui::{self, Align2, Color32, RichText},
+    EguiContexts,
+};
+use bevy_persistent::prelude::Persistent;
+
+use crate::{
+    achievements::{AchievementProgress, AchievementToast},
+    game_states::menu::MenuPage,
+    manifest::achievement::AchievementManifest,
+};
+
+pub fn draw_achievement_toasts(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    mut toasts: Query<(&mut AchievementToast, Entity)>,
+    time: Res<Time>,
+) {
+    for (i, (mut toast, entity)) in toasts.iter_mut().enumerate() {
+        egui::Area::new(egui::Id::new("achievement_toast").with(entity))
+            .anchor(Align2::RIGHT_BOTTOM, [-16., -16. - i as f32 * 70.])
+            .show(contexts.ctx_mut(), |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(
+                        RichText::new("Achievement unlocked")
+                            .size(14.)
+                            .color(Color32::GOLD),
+                    );
+                    ui.label(RichText::new(&toast.0).size(20.).strong());
+                });
+            });
+
+        toast.1.tick(time.delta());
+
+        if toast.1.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+pub fn draw_achievements_menu(
+    mut contexts: EguiContexts,
+    progress: Res<Persistent<AchievementProgress>>,
+    achievement_manifest: Res<AchievementManifest>,
+    mut page: ResMut<MenuPage>,
+) {
+    let mut achievements = achievement_manifest
+        .achievements
+        .values()
+        .collect::<Vec<_>>();
+    achievements.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let unlocked_count = achievements
+        .iter()
+        .filter(|a| progress.unlocked.contains(&a.name))
+        .count();
+
+    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+        ui.heading(RichText::new("Achievements").size(32.));
+        ui.label(format!(
+            "{}/{} unlocked",
+            unlocked_count,
+            achievements.len()
+        ));
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for achievement in achievements {
+                let unlocked = progress.unlocked.contains(&achievement.name);
+
+                let color = if unlocked {
+                    Color32::GOLD
+                } else {
+                    Color32::GRAY
+                };
+
+                let description = if achievement.hidden && !unlocked {
+                    "???"
+                } else {
+                    achievement.description.as_str()
+                };
+
+                ui.group(|ui| {
+                    ui.label(
+                        RichText::new(&achievement.name)
+                            .size(20.)
+                            .strong()
+                            .color(color),
+                    );
+                    ui.label(description);
+                });
+            }
+        });
+
+        ui.add_space(10.);
+
+        if ui.button(RichText::new("Back").size(20.)).clicked() {
+            *page = MenuPage::Main;
+        }
+    });
+}
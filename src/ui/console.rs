@@ -0,0 +1,120 @@
+use bevy::prelude::{EventWriter, Res, ResMut};
+use bevy_egui::{
+    egui::{
+        self,
+        text::{CCursor, CCursorRange},
+        Align2, Color32, Id, Key, Modifiers, Order, RichText, ScrollArea, TextEdit, TextStyle,
+    },
+    EguiContexts,
+};
+
+use crate::{
+    console::{completions, Console, ConsoleCommandEvent},
+    manifest::{enemy::EnemyManifest, floor::DomainManifest},
+};
+
+const BACKGROUND_COLOR: Color32 = Color32::from_rgba_premultiplied(10, 0, 15, 230);
+const ERROR_COLOR: Color32 = Color32::from_rgb(230, 90, 90);
+const HINT_COLOR: Color32 = Color32::from_gray(140);
+
+pub fn draw_console(
+    mut contexts: EguiContexts,
+    mut console: ResMut<Console>,
+    domain_manifest: Res<DomainManifest>,
+    enemy_manifest: Res<EnemyManifest>,
+    mut writer: EventWriter<ConsoleCommandEvent>,
+) {
+    if !console.open {
+        return;
+    }
+
+    let ctx = contexts.ctx_mut();
+    let screen = ctx.screen_rect();
+    let input_id = Id::new("console_input");
+
+    //The keys used by the console are taken before the text field sees them,
+    //the toggle key would be typed and tab would move the focus away
+    let (tab, up, down) = ctx.input_mut(|input| {
+        input
+            .events
+            .retain(|event| !matches!(event, egui::Event::Text(text) if text == "`"));
+
+        (
+            input.consume_key(Modifiers::NONE, Key::Tab),
+            input.consume_key(Modifiers::NONE, Key::ArrowUp),
+            input.consume_key(Modifiers::NONE, Key::ArrowDown),
+        )
+    });
+
+    let suggestions = completions(&console.input, &domain_manifest, &enemy_manifest);
+    let mut moved = true;
+
+    if tab && !suggestions.is_empty() {
+        console.input = suggestions[0].clone();
+    } else if up {
+        console.history_previous();
+    } else if down {
+        console.history_next();
+    } else {
+        moved = false;
+    }
+
+    //Text set from outside the field leaves the cursor where it was
+    if moved {
+        if let Some(mut state) = TextEdit::load_state(ctx, input_id) {
+            let end = CCursor::new(console.input.chars().count());
+            state.cursor.set_char_range(Some(CCursorRange::one(end)));
+            state.store(ctx, input_id);
+        }
+    }
+
+    egui::Area::new(Id::new("console"))
+        .anchor(Align2::CENTER_TOP, egui::vec2(0., 0.))
+        .order(Order::Foreground)
+        .show(ctx, |ui| {
+            egui::Frame::none()
+                .fill(BACKGROUND_COLOR)
+                .inner_margin(8.)
+                .show(ui, |ui| {
+                    ui.set_width(screen.width() - 16.);
+
+                    ScrollArea::vertical()
+                        .max_height(screen.height() * 0.35)
+                        .auto_shrink([false, true])
+                        .stick_to_bottom(true)
+                        .show(ui, |ui| {
+                            for (text, error) in console.lines.iter() {
+                                let color = if *error { ERROR_COLOR } else { Color32::WHITE };
+
+                                ui.label(RichText::new(text).monospace().color(color));
+                            }
+                        });
+
+                    ui.separator();
+
+                    let response = ui.add(
+                        TextEdit::singleline(&mut console.input)
+                            .id(input_id)
+                            .font(TextStyle::Monospace)
+                            .hint_text("Type help for the list of commands")
+                            .desired_width(f32::INFINITY),
+                    );
+
+                    if response.lost_focus() && ui.input(|input| input.key_pressed(Key::Enter)) {
+                        if let Some(command) = console.submit() {
+                            writer.send(command);
+                        }
+                    }
+
+                    response.request_focus();
+
+                    if !suggestions.is_empty() {
+                        ui.label(
+                            RichText::new(format!("Tab: {}", suggestions.join("   ")))
+                                .monospace()
+                                .color(HINT_COLOR),
+                        );
+                    }
+                });
+        });
+}
@@ -0,0 +1,154 @@
+use bevy::{
+    input::{gamepad::GamepadButton, ButtonInput},
+    prelude::{KeyCode, MouseButton, Res, ResMut, Resource},
+};
+use bevy_egui::{
+    egui::{self, Color32, RichText},
+    EguiContexts,
+};
+use bevy_persistent::prelude::Persistent;
+
+use crate::{
+    controls::{Controls, Device},
+    game_states::menu::MenuPage,
+    player::PlayerActions,
+};
+
+const CONFLICT_COLOR: Color32 = Color32::from_rgb(230, 70, 70);
+
+//Binding slot waiting for an input
+#[derive(Resource, Default)]
+pub struct Rebinding(pub Option<(PlayerActions, Device)>);
+
+pub fn draw_controls_menu(
+    mut contexts: EguiContexts,
+    mut controls: ResMut<Persistent<Controls>>,
+    mut page: ResMut<MenuPage>,
+    mut rebinding: ResMut<Rebinding>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+) {
+    let listening = &mut rebinding.0;
+    let mut bound = false; // The gamepad also clicks the focused button with the input just bound
+
+    if let Some((action, device)) = *listening {
+        let binding = controls.binding(action);
+        let mut new_binding = binding;
+
+        if keys.just_pressed(KeyCode::Escape) {
+            *listening = None;
+        } else if keys.just_pressed(KeyCode::Backspace) {
+            new_binding.clear(device);
+        } else {
+            match device {
+                Device::Keyboard => {
+                    if let Some(key) = keys.get_just_pressed().next() {
+                        new_binding.key = Some(*key);
+                    }
+                }
+                Device::Mouse => {
+                    if let Some(button) = mouse.get_just_pressed().next() {
+                        new_binding.mouse = Some(*button);
+                    }
+                }
+                Device::Gamepad => {
+                    if let Some(button) = gamepad_buttons.get_just_pressed().next() {
+                        new_binding.gamepad = Some(button.button_type);
+                    }
+                }
+            }
+        }
+
+        if new_binding != binding {
+            controls.rebind(action, device, new_binding);
+            let _ = controls.persist();
+            *listening = None;
+            bound = true;
+        }
+    }
+
+    let mut conflicts = Vec::new();
+
+    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+        ui.heading(RichText::new("Controls").size(32.));
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("controls")
+                .num_columns(4)
+                .spacing([30., 8.])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label(RichText::new("Action").size(20.).strong());
+                    for header in ["Keyboard", "Mouse", "Gamepad"] {
+                        ui.label(RichText::new(header).size(20.).strong());
+                    }
+                    ui.end_row();
+
+                    for action in PlayerActions::REBINDABLE {
+                        let binding = controls.binding(action);
+
+                        ui.label(RichText::new(action.name()).size(18.));
+
+                        for device in Device::ALL {
+                            let conflicting = controls.conflicts(action, device);
+
+                            let mut text = if *listening == Some((action, device)) {
+                                RichText::new("Press...")
+                            } else {
+                                RichText::new(
+                                    binding.label(device).unwrap_or_else(|| "-".to_string()),
+                                )
+                            };
+
+                            if !conflicting.is_empty() {
+                                text = text.color(CONFLICT_COLOR);
+                                conflicts.push((action, conflicting));
+                            }
+
+                            if ui.button(text.size(18.)).clicked() && !bound {
+                                *listening = Some((action, device));
+                            }
+                        }
+
+                        ui.end_row();
+                    }
+                });
+        });
+
+        ui.add_space(10.);
+        ui.label("Click a binding and press the new input. Escape cancels, Backspace clears it.");
+
+        for (action, others) in conflicts.iter() {
+            let others = others
+                .iter()
+                .map(|other| other.name())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            ui.colored_label(
+                CONFLICT_COLOR,
+                format!("{} shares an input with {}", action.name(), others),
+            );
+        }
+
+        ui.add_space(10.);
+
+        ui.horizontal(|ui| {
+            if ui.button(RichText::new("Back").size(20.)).clicked() {
+                *listening = None;
+                *page = MenuPage::Main;
+            }
+
+            if ui
+                .button(RichText::new("Reset to defaults").size(20.))
+                .clicked()
+            {
+                **controls = Controls::default();
+                let _ = controls.persist();
+                *listening = None;
+            }
+        });
+    });
+}
@@ -0,0 +1,66 @@
+use bevy::prelude::{Camera, GlobalTransform, Query, Res, With, Without};
+use bevy_egui::{
+    egui::{Color32, Id, LayerId, Order, Pos2, Shape, Stroke},
+    EguiContexts,
+};
+
+use crate::{death::Dying, enemy::Enemy, floor::FloorResource, movement::movement::Follow};
+
+const STRAGGLER_COUNT: usize = 3; // Arrows show up once this many enemies or less are left
+const EDGE_MARGIN: f32 = 28.;
+const ARROW_SIZE: f32 = 14.;
+
+//Points at the last enemies of the floor when they are off screen
+pub fn draw_straggler_arrows(
+    mut contexts: EguiContexts,
+    floor: Res<FloorResource>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<Follow>>,
+    enemies: Query<&GlobalTransform, (With<Enemy>, Without<Dying>)>,
+) {
+    let remaining = floor
+        .enemies
+        .iter()
+        .filter_map(|entity| enemies.get(*entity).ok())
+        .collect::<Vec<_>>();
+
+    if remaining.is_empty() || remaining.len() > STRAGGLER_COUNT {
+        return;
+    }
+
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
+
+    let ctx = contexts.ctx_mut();
+    let screen = ctx.screen_rect().shrink(EDGE_MARGIN);
+    let painter = ctx.layer_painter(LayerId::new(Order::Background, Id::new("straggler_arrows")));
+
+    for transform in remaining {
+        let Some(position) = camera.world_to_viewport(camera_transform, transform.translation())
+        else {
+            continue;
+        };
+
+        let position = Pos2::new(position.x, position.y);
+
+        if screen.contains(position) {
+            continue;
+        }
+
+        //Where the line from the center of the screen to the enemy crosses the edge
+        let offset = position - screen.center();
+        let scale =
+            (screen.width() / 2. / offset.x.abs()).min(screen.height() / 2. / offset.y.abs());
+        let tip = screen.center() + offset * scale;
+
+        let direction = offset.normalized();
+        let side = direction.rot90() * ARROW_SIZE / 2.;
+        let base = tip - direction * ARROW_SIZE;
+
+        painter.add(Shape::convex_polygon(
+            vec![tip, base + side, base - side],
+            Color32::from_rgb(220, 60, 60),
+            Stroke::new(1., Color32::WHITE),
+        ));
+    }
+}
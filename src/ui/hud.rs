@@ -0,0 +1,332 @@
+use bevy::{
+    math::Vec3Swizzles,
+    prelude::{Query, Res, Transform, Window, With, Without},
+    time::Time,
+    window::PrimaryWindow,
+};
+use bevy_egui::{
+    egui::{
+        self, load::SizedTexture, Align2, Color32, FontId, Id, LayerId, Order, Pos2, Rect,
+        RichText, Sense, Stroke,
+    },
+    EguiContexts,
+};
+use bevy_persistent::prelude::Persistent;
+
+use crate::{
+    controller::{Combo, ParryAbility, RangedAttack},
+    controls::{ActiveDevice, Controls},
+    floor::FloorResource,
+    hud::{ui_scale, HudState, HudTextures, HIT_WINDOW},
+    player::{Player, PlayerActions},
+    stats::{Cooldown, Damage, Health, Level, MovementSpeed, Revenge},
+    weapon::{WeaponPickup, PICKUP_DISTANCE},
+};
+
+const TRAIL_TINT: Color32 = Color32::from_rgb(255, 230, 160);
+const COOLDOWN_SHADE: Color32 = Color32::from_rgba_premultiplied(0, 0, 0, 170);
+const TILE_COLOR: Color32 = Color32::from_rgba_premultiplied(20, 10, 25, 200);
+const COMBO_COLOR: Color32 = Color32::from_rgb(255, 200, 80);
+
+//Health bar with a trail of the damage just taken, and the player stats
+pub fn draw_hud(
+    mut contexts: EguiContexts,
+    textures: Res<HudTextures>,
+    hud: Res<HudState>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    query: Query<(&Health, &Damage, &MovementSpeed, &Level, &Revenge), With<Player>>,
+    floor: Res<FloorResource>,
+) {
+    let (Ok(window), Ok((health, damage, speed, level, revenge))) =
+        (windows.get_single(), query.get_single())
+    else {
+        return;
+    };
+
+    let scale = ui_scale(window);
+    let ctx = contexts.ctx_mut();
+
+    egui::Area::new(Id::new("hud_health"))
+        .anchor(Align2::LEFT_TOP, egui::vec2(16., 24. * scale))
+        .interactable(false)
+        .show(ctx, |ui| {
+            let size = egui::vec2(63. * 4. * scale, 10. * 4. * scale);
+            let (rect, _) = ui.allocate_exact_size(size, Sense::hover());
+
+            let paint_fill = |ratio: f32, tint: Color32| {
+                egui::Image::new(SizedTexture::new(textures.health_fill, size))
+                    .uv(Rect::from_min_max(Pos2::ZERO, egui::pos2(ratio, 1.)))
+                    .tint(tint)
+                    .paint_at(
+                        ui,
+                        Rect::from_min_size(rect.min, egui::vec2(size.x * ratio, size.y)),
+                    );
+            };
+
+            let ratio = health.current as f32 / health.max as f32;
+
+            egui::Image::new(SizedTexture::new(textures.health_border, size)).paint_at(ui, rect);
+            paint_fill(hud.health_trail.max(ratio), TRAIL_TINT);
+            paint_fill(ratio, Color32::WHITE);
+
+            ui.painter().text(
+                rect.center(),
+                Align2::CENTER_CENTER,
+                format!("{}/{}", health.current, health.max),
+                FontId::proportional(16. * scale),
+                Color32::WHITE,
+            );
+
+            ui.add_space(4. * scale);
+            ui.label(
+                RichText::new(format!("Level {}", level.level))
+                    .size(22. * scale)
+                    .strong(),
+            );
+            ui.label(
+                RichText::new(format!(
+                    "Damage {}   Speed {}",
+                    (damage.amount as f32 * revenge.damage_scale()).round(),
+                    (speed.speed as f32 * revenge.speed_scale()).round()
+                ))
+                .size(16. * scale),
+            );
+        });
+
+    egui::Area::new(Id::new("hud_floor"))
+        .anchor(Align2::RIGHT_TOP, egui::vec2(-16., 24. * scale))
+        .interactable(false)
+        .show(ctx, |ui| {
+            ui.label(
+                RichText::new(format!("Floor {}", floor.floor))
+                    .size(26. * scale)
+                    .strong(),
+            );
+            ui.label(RichText::new(format!("Enemies {}", floor.enemies.len())).size(16. * scale));
+        });
+}
+
+//XP and revenge along the top of the screen
+pub fn draw_top_bars(
+    mut contexts: EguiContexts,
+    hud: Res<HudState>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    query: Query<&Revenge, With<Player>>,
+    time: Res<Time>,
+) {
+    let (Ok(window), Ok(revenge)) = (windows.get_single(), query.get_single()) else {
+        return;
+    };
+
+    let scale = ui_scale(window);
+    let height = 8. * scale;
+    let width = window.width();
+
+    let painter = contexts
+        .ctx_mut()
+        .layer_painter(LayerId::new(Order::Background, Id::new("hud_bars")));
+
+    painter.rect_filled(
+        Rect::from_min_size(Pos2::ZERO, egui::vec2(width * hud.xp_fill, height)),
+        0.,
+        Color32::DARK_BLUE,
+    );
+
+    //Pulses once full and waiting to be activated
+    let color = if revenge.is_ready() {
+        let pulse = (time.elapsed_seconds() * 6.).sin() * 0.5 + 0.5;
+        Color32::RED.gamma_multiply(0.7 + pulse * 0.3)
+    } else {
+        Color32::DARK_RED
+    };
+
+    painter.rect_filled(
+        Rect::from_min_size(
+            egui::pos2(0., height),
+            egui::vec2(
+                width * (revenge.amount / revenge.total).clamp(0., 1.),
+                height,
+            ),
+        ),
+        0.,
+        color,
+    );
+}
+
+//Abilities with their input and the time left before they can be used again
+pub fn draw_cooldowns(
+    mut contexts: EguiContexts,
+    controls: Res<Persistent<Controls>>,
+    active: Res<ActiveDevice>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    query: Query<(&Cooldown, &RangedAttack, &ParryAbility), With<Player>>,
+) {
+    let (Ok(window), Ok((cooldown, secondary, parry))) = (windows.get_single(), query.get_single())
+    else {
+        return;
+    };
+
+    let scale = ui_scale(window);
+    let tile = 44. * scale;
+
+    //Attacking and dashing share the same cooldown
+    let abilities = [
+        (PlayerActions::Attack, "Attack", cooldown.timer.fraction()),
+        (PlayerActions::Dash, "Dash", cooldown.timer.fraction()),
+        (
+            PlayerActions::SecondaryAttack,
+            "Shoot",
+            secondary.cooldown.fraction(),
+        ),
+        (PlayerActions::Parry, "Parry", parry.cooldown.fraction()),
+    ];
+
+    egui::Area::new(Id::new("hud_cooldowns"))
+        .anchor(Align2::CENTER_BOTTOM, egui::vec2(0., -16. * scale))
+        .interactable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                for (action, name, ready) in abilities {
+                    ui.vertical_centered(|ui| {
+                        let (rect, _) =
+                            ui.allocate_exact_size(egui::vec2(tile, tile), Sense::hover());
+                        let painter = ui.painter();
+
+                        painter.rect_filled(rect, 4. * scale, TILE_COLOR);
+
+                        //Shade shrinks from the top as the cooldown runs out
+                        if ready < 1. {
+                            let mut shade = rect;
+                            shade.set_top(rect.bottom() - rect.height() * (1. - ready));
+                            painter.rect_filled(shade, 4. * scale, COOLDOWN_SHADE);
+                        }
+
+                        painter.rect_stroke(
+                            rect,
+                            4. * scale,
+                            Stroke::new(1., Color32::from_gray(if ready < 1. { 90 } else { 200 })),
+                        );
+
+                        let input = controls
+                            .binding(action)
+                            .prompt(*active)
+                            .unwrap_or_else(|| "-".to_string());
+
+                        painter.text(
+                            rect.center(),
+                            Align2::CENTER_CENTER,
+                            input,
+                            FontId::proportional(16. * scale),
+                            Color32::WHITE,
+                        );
+
+                        ui.label(RichText::new(name).size(13. * scale));
+                    });
+                }
+            });
+        });
+}
+
+//Hits landed in a row and the step of the current attack chain
+pub fn draw_combo_counter(
+    mut contexts: EguiContexts,
+    hud: Res<HudState>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    query: Query<Option<&Combo>, With<Player>>,
+) {
+    let (Ok(window), Ok(combo)) = (windows.get_single(), query.get_single()) else {
+        return;
+    };
+
+    if hud.hits < 2 && combo.is_none() {
+        return;
+    }
+
+    let scale = ui_scale(window);
+
+    egui::Area::new(Id::new("hud_combo"))
+        .anchor(Align2::RIGHT_CENTER, egui::vec2(-24. * scale, 0.))
+        .interactable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            if hud.hits >= 2 {
+                //Pops when a hit lands and fades out before resetting
+                let pop = ((hud.hits_timer - (HIT_WINDOW - 0.15)) / 0.15).clamp(0., 1.);
+                let alpha = (hud.hits_timer / 0.5).clamp(0., 1.);
+
+                ui.label(
+                    RichText::new(format!("{} hits", hud.hits))
+                        .size((28. + 10. * pop) * scale)
+                        .strong()
+                        .color(COMBO_COLOR.gamma_multiply(alpha)),
+                );
+            }
+
+            if let Some(combo) = combo {
+                ui.horizontal(|ui| {
+                    for step in 0..=combo.max {
+                        let (rect, _) =
+                            ui.allocate_exact_size(egui::vec2(10., 10.) * scale, Sense::hover());
+
+                        let color = if step <= combo.current {
+                            COMBO_COLOR
+                        } else {
+                            Color32::from_gray(70)
+                        };
+
+                        ui.painter().circle_filled(rect.center(), 4. * scale, color);
+                    }
+                });
+            }
+        });
+}
+
+//Prompts for what the player can do right now, for the device they are using
+pub fn draw_action_prompts(
+    mut contexts: EguiContexts,
+    controls: Res<Persistent<Controls>>,
+    active: Res<ActiveDevice>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    query: Query<(&Transform, &Revenge), With<Player>>,
+    pickups: Query<(&WeaponPickup, &Transform), Without<Player>>,
+) {
+    let (Ok(window), Ok((player_transform, revenge))) = (windows.get_single(), query.get_single())
+    else {
+        return;
+    };
+
+    let scale = ui_scale(window);
+    let player_pos = player_transform.translation.xy();
+
+    let mut prompts = Vec::new();
+
+    if revenge.manual && revenge.is_ready() {
+        prompts.push((PlayerActions::ActivateRevenge, "Revenge!".to_string()));
+    }
+
+    if let Some((pickup, _)) = pickups
+        .iter()
+        .find(|(_, transform)| transform.translation.xy().distance(player_pos) < PICKUP_DISTANCE)
+    {
+        prompts.push((PlayerActions::Interact, format!("Pick up {}", pickup.0)));
+    }
+
+    egui::Area::new(Id::new("action_prompts"))
+        .anchor(Align2::LEFT_BOTTOM, egui::vec2(16., -16. * scale))
+        .interactable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            for (action, text) in prompts {
+                let Some(input) = controls.binding(action).prompt(*active) else {
+                    continue;
+                };
+
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new(format!("[{}]", input))
+                            .size(18. * scale)
+                            .strong(),
+                    );
+                    ui.label(RichText::new(text).size(18. * scale));
+                });
+            }
+        });
+}
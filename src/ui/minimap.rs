@@ -0,0 +1,254 @@
+use bevy::{
+    math::{Vec2, Vec3Swizzles},
+    prelude::{Has, Or, Query, Res, Transform, Window, With, Without},
+    window::PrimaryWindow,
+};
+use bevy_ecs_tilemap::tiles::TilePos;
+use bevy_egui::{
+    egui::{self, Align2, Color32, Rect, Shape, Stroke},
+    EguiContexts,
+};
+
+use crate::{
+    boss::Boss, death::Dying, enemy::Enemy, heal::Fountain, hud::ui_scale, minimap::Minimap,
+    player::Player, weapon::WeaponPickup,
+};
+
+const MINIMAP_SIZE: f32 = 180.;
+const MINIMAP_SCALE: f32 = 0.12; // Minimap pixels per world unit
+const OVERLAY_MARGIN: f32 = 60.;
+
+const BACKGROUND_COLOR: Color32 = Color32::from_rgba_premultiplied(10, 0, 15, 200);
+const TILE_COLOR: Color32 = Color32::from_rgb(90, 80, 110);
+const PLAYER_COLOR: Color32 = Color32::from_rgb(80, 220, 120);
+const ENEMY_COLOR: Color32 = Color32::from_rgb(220, 60, 60);
+const PORTAL_COLOR: Color32 = Color32::from_rgb(190, 90, 255);
+const START_COLOR: Color32 = Color32::from_rgb(200, 200, 200);
+const FOUNTAIN_COLOR: Color32 = Color32::from_rgb(80, 200, 230);
+const WEAPON_COLOR: Color32 = Color32::from_rgb(240, 200, 80);
+
+//Everything shown on the map besides the tiles, in world space
+struct Markers {
+    player: Vec2,
+    enemies: Vec<(Vec2, bool)>, // Position, is a boss
+    fountains: Vec<Vec2>,
+    weapons: Vec<Vec2>,
+}
+
+fn collect_markers(
+    minimap: &Minimap,
+    player: Vec2,
+    enemies: &Query<(&Transform, Has<Boss>), (Or<(With<Enemy>, With<Boss>)>, Without<Dying>)>,
+    fountains: &Query<(&Transform, &Fountain)>,
+    weapons: &Query<&Transform, With<WeaponPickup>>,
+) -> Markers {
+    //Only what stands on explored tiles is known
+    Markers {
+        player,
+        enemies: enemies
+            .iter()
+            .map(|(transform, is_boss)| (transform.translation.xy(), is_boss))
+            .filter(|(pos, _)| minimap.is_revealed(*pos))
+            .collect(),
+        fountains: fountains
+            .iter()
+            .filter(|(_, fountain)| !fountain.used)
+            .map(|(transform, _)| transform.translation.xy())
+            .filter(|pos| minimap.is_revealed(*pos))
+            .collect(),
+        weapons: weapons
+            .iter()
+            .map(|transform| transform.translation.xy())
+            .filter(|pos| minimap.is_revealed(*pos))
+            .collect(),
+    }
+}
+
+//Draws the explored tiles and markers with the world position `center` in the middle of `rect`
+fn paint_map(
+    painter: &egui::Painter,
+    rect: Rect,
+    minimap: &Minimap,
+    markers: &Markers,
+    center: Vec2,
+    scale: f32,
+) {
+    let to_screen = |pos: Vec2| {
+        let offset = (pos - center) * scale;
+        rect.center() + egui::vec2(offset.x, -offset.y)
+    };
+
+    painter.rect_filled(rect, 4., BACKGROUND_COLOR);
+
+    let painter = painter.with_clip_rect(rect);
+
+    //Tiles are 32x16 diamonds in world space
+    let half = egui::vec2(16. * scale, 8. * scale);
+
+    for tile in minimap.revealed.iter() {
+        let Some(pos) = minimap.tiles.get(tile) else {
+            continue;
+        };
+
+        let pos = to_screen(*pos);
+
+        painter.add(Shape::convex_polygon(
+            vec![
+                pos + egui::vec2(0., -half.y),
+                pos + egui::vec2(half.x, 0.),
+                pos + egui::vec2(0., half.y),
+                pos + egui::vec2(-half.x, 0.),
+            ],
+            TILE_COLOR,
+            Stroke::NONE,
+        ));
+    }
+
+    let radius = (24. * scale).max(2.);
+
+    let tile_marker = |tile: Option<TilePos>, color: Color32| {
+        if let Some(pos) = tile
+            .filter(|tile| minimap.revealed.contains(tile))
+            .and_then(|tile| minimap.tiles.get(&tile))
+        {
+            painter.circle_filled(to_screen(*pos), radius * 1.5, color);
+        }
+    };
+
+    tile_marker(minimap.start, START_COLOR);
+    tile_marker(minimap.portal, PORTAL_COLOR);
+
+    for pos in markers.fountains.iter() {
+        painter.circle_filled(to_screen(*pos), radius, FOUNTAIN_COLOR);
+    }
+
+    for pos in markers.weapons.iter() {
+        painter.circle_filled(to_screen(*pos), radius, WEAPON_COLOR);
+    }
+
+    for (pos, is_boss) in markers.enemies.iter() {
+        let size = if *is_boss { radius * 2. } else { radius };
+        painter.circle_filled(to_screen(*pos), size, ENEMY_COLOR);
+    }
+
+    painter.circle(
+        to_screen(markers.player),
+        radius * 1.25,
+        PLAYER_COLOR,
+        Stroke::new(1., Color32::WHITE),
+    );
+}
+
+pub fn draw_minimap(
+    mut contexts: EguiContexts,
+    minimap: Res<Minimap>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    player_query: Query<&Transform, With<Player>>,
+    enemies: Query<(&Transform, Has<Boss>), (Or<(With<Enemy>, With<Boss>)>, Without<Dying>)>,
+    fountains: Query<(&Transform, &Fountain)>,
+    weapons: Query<&Transform, With<WeaponPickup>>,
+) {
+    if minimap.open {
+        return;
+    }
+
+    let (Ok(window), Ok(player)) = (windows.get_single(), player_query.get_single()) else {
+        return;
+    };
+
+    let scale = ui_scale(window);
+    let player = player.translation.xy();
+    let markers = collect_markers(&minimap, player, &enemies, &fountains, &weapons);
+
+    egui::Area::new(egui::Id::new("minimap"))
+        .anchor(Align2::RIGHT_TOP, egui::vec2(-16., 84. * scale))
+        .interactable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            let (rect, _) = ui.allocate_exact_size(
+                egui::Vec2::splat(MINIMAP_SIZE * scale),
+                egui::Sense::hover(),
+            );
+
+            paint_map(
+                ui.painter(),
+                rect,
+                &minimap,
+                &markers,
+                player,
+                MINIMAP_SCALE * scale,
+            );
+        });
+}
+
+//Whole floor scaled to fit the screen
+pub fn draw_map_overlay(
+    mut contexts: EguiContexts,
+    minimap: Res<Minimap>,
+    player_query: Query<&Transform, With<Player>>,
+    enemies: Query<(&Transform, Has<Boss>), (Or<(With<Enemy>, With<Boss>)>, Without<Dying>)>,
+    fountains: Query<(&Transform, &Fountain)>,
+    weapons: Query<&Transform, With<WeaponPickup>>,
+) {
+    if !minimap.open {
+        return;
+    }
+
+    let (Ok(player), Some(bounds)) = (player_query.get_single(), minimap.bounds()) else {
+        return;
+    };
+
+    let markers = collect_markers(
+        &minimap,
+        player.translation.xy(),
+        &enemies,
+        &fountains,
+        &weapons,
+    );
+
+    let ctx = contexts.ctx_mut();
+    let screen = ctx.screen_rect().shrink(OVERLAY_MARGIN);
+
+    //Padding of one tile around the floor
+    let size = bounds.size() + Vec2::new(64., 32.);
+    let scale = (screen.width() / size.x).min(screen.height() / size.y);
+
+    egui::Area::new(egui::Id::new("map_overlay"))
+        .fixed_pos(screen.min)
+        .interactable(false)
+        .show(ctx, |ui| {
+            let (rect, _) = ui.allocate_exact_size(screen.size(), egui::Sense::hover());
+
+            paint_map(
+                ui.painter(),
+                rect,
+                &minimap,
+                &markers,
+                bounds.center(),
+                scale,
+            );
+
+            let legend = [
+                ("You", PLAYER_COLOR),
+                ("Enemy", ENEMY_COLOR),
+                ("Portal", PORTAL_COLOR),
+                ("Start", START_COLOR),
+                ("Fountain", FOUNTAIN_COLOR),
+                ("Weapon", WEAPON_COLOR),
+            ];
+
+            let mut pos = rect.left_top() + egui::vec2(12., 12.);
+
+            for (label, color) in legend {
+                ui.painter()
+                    .circle_filled(pos + egui::vec2(6., 9.), 5., color);
+                ui.painter().text(
+                    pos + egui::vec2(18., 0.),
+                    Align2::LEFT_TOP,
+                    label,
+                    egui::FontId::proportional(18.),
+                    Color32::WHITE,
+                );
+                pos.y += 22.;
+            }
+        });
+}
@@ -0,0 +1,125 @@
+use bevy::prelude::{Local, ResMut};
+use bevy_egui::{
+    egui::{self, RichText},
+    EguiContexts,
+};
+use bevy_persistent::prelude::Persistent;
+
+use crate::{
+    game_states::menu::MenuPage,
+    settings::{DisplayMode, Settings, RESOLUTIONS},
+};
+
+pub fn draw_settings_menu(
+    mut contexts: EguiContexts,
+    mut settings: ResMut<Persistent<Settings>>,
+    mut page: ResMut<MenuPage>,
+    mut unsaved: Local<bool>,
+) {
+    //Edited on a copy so the settings only count as changed when something differs
+    let mut edited = settings.get().clone();
+    let mut back = false;
+
+    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+        ui.heading(RichText::new("Settings").size(32.));
+        ui.separator();
+
+        egui::Grid::new("settings")
+            .num_columns(2)
+            .spacing([30., 10.])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label(RichText::new("Window mode").size(18.));
+                egui::ComboBox::from_id_source("display_mode")
+                    .selected_text(edited.display_mode.name())
+                    .show_ui(ui, |ui| {
+                        for mode in DisplayMode::ALL {
+                            ui.selectable_value(&mut edited.display_mode, mode, mode.name());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(RichText::new("Resolution").size(18.));
+                ui.add_enabled_ui(edited.display_mode == DisplayMode::Windowed, |ui| {
+                    let (width, height) = edited.resolution;
+
+                    egui::ComboBox::from_id_source("resolution")
+                        .selected_text(format!("{}x{}", width, height))
+                        .show_ui(ui, |ui| {
+                            for (width, height) in RESOLUTIONS {
+                                ui.selectable_value(
+                                    &mut edited.resolution,
+                                    (width, height),
+                                    format!("{}x{}", width, height),
+                                );
+                            }
+                        });
+                });
+                ui.end_row();
+
+                ui.label(RichText::new("VSync").size(18.));
+                ui.checkbox(&mut edited.vsync, "");
+                ui.end_row();
+
+                ui.label(RichText::new("Camera zoom").size(18.));
+                ui.add(egui::Slider::new(&mut edited.zoom, 0.5..=2.).suffix("x"));
+                ui.end_row();
+
+                ui.label(RichText::new("Screen shake").size(18.));
+                ui.add(percent_slider(&mut edited.shake));
+                ui.end_row();
+
+                ui.label(RichText::new("Damage numbers").size(18.));
+                ui.checkbox(&mut edited.damage_numbers, "");
+                ui.end_row();
+
+                ui.label(RichText::new("Debug overlay").size(18.));
+                ui.checkbox(&mut edited.debug_overlay, "");
+                ui.end_row();
+
+                ui.label(RichText::new("Master volume").size(18.));
+                ui.add(percent_slider(&mut edited.master_volume));
+                ui.end_row();
+            });
+
+        ui.add_space(10.);
+
+        ui.horizontal(|ui| {
+            if ui.button(RichText::new("Back").size(20.)).clicked() {
+                *page = MenuPage::Main;
+                back = true;
+            }
+
+            if ui
+                .button(RichText::new("Reset to defaults").size(20.))
+                .clicked()
+            {
+                edited = Settings::default();
+            }
+        });
+    });
+
+    //Changes apply right away, but a dragged slider is only saved once it's released
+    if edited != *settings.get() {
+        *settings.get_mut() = edited;
+        *unsaved = true;
+    }
+
+    let dragging = contexts.ctx_mut().dragged_id().is_some();
+
+    if *unsaved && (!dragging || back) {
+        let _ = settings.persist();
+        *unsaved = false;
+    }
+}
+
+fn percent_slider(value: &mut f32) -> egui::Slider<'_> {
+    egui::Slider::new(value, 0.0..=1.)
+        .custom_formatter(|value, _| format!("{:.0}%", value * 100.))
+        .custom_parser(|text| {
+            text.trim_end_matches('%')
+                .parse::<f64>()
+                .ok()
+                .map(|value| value / 100.)
+        })
+}
@@ -0,0 +1,85 @@
+use bevy::prelude::{Res, ResMut};
+use bevy_egui::{
+    egui::{self, Align, Button, Layout, RichText},
+    EguiContexts,
+};
+use bevy_persistent::prelude::Persistent;
+
+use crate::{game_states::menu::MenuPage, manifest::unlock::UnlockManifest, meta::MetaProgression};
+
+pub fn draw_unlocks_menu(
+    mut contexts: EguiContexts,
+    mut meta: ResMut<Persistent<MetaProgression>>,
+    unlock_manifest: Res<UnlockManifest>,
+    mut page: ResMut<MenuPage>,
+) {
+    let mut unlocks = unlock_manifest.unlocks.values().collect::<Vec<_>>();
+    unlocks.sort_by(|a, b| {
+        a.costs
+            .first()
+            .cmp(&b.costs.first())
+            .then(a.name.cmp(&b.name))
+    });
+
+    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+        ui.horizontal(|ui| {
+            ui.heading(RichText::new("Unlocks").size(32.));
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                ui.label(RichText::new(format!("Souls: {}", meta.currency)).size(22.));
+            });
+        });
+
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for unlock in unlocks {
+                let level = meta.level(&unlock.name);
+
+                ui.group(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.vertical(|ui| {
+                            ui.label(
+                                RichText::new(format!(
+                                    "{} ({}/{})",
+                                    unlock.name,
+                                    level,
+                                    unlock.max_level()
+                                ))
+                                .size(20.)
+                                .strong(),
+                            );
+                            ui.label(&unlock.description);
+
+                            if let Some(required) = &unlock.requires {
+                                if meta.level(required) == 0 {
+                                    ui.label(format!("Requires {}", required));
+                                }
+                            }
+                        });
+
+                        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                            let text = match unlock.cost(level) {
+                                Some(cost) => format!("Buy ({})", cost),
+                                None => "Maxed".to_string(),
+                            };
+
+                            if ui
+                                .add_enabled(meta.can_buy(unlock), Button::new(text))
+                                .clicked()
+                                && meta.buy(unlock)
+                            {
+                                let _ = meta.persist();
+                            }
+                        });
+                    });
+                });
+            }
+        });
+
+        ui.add_space(10.);
+
+        if ui.button(RichText::new("Back").size(20.)).clicked() {
+            *page = MenuPage::Main;
+        }
+    });
+}
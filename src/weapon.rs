@@ -0,0 +1,254 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+use leafwing_input_manager::prelude::ActionState;
+use leafwing_manifest::manifest::Manifest;
+use seldom_state::prelude::StateMachine;
+use turborand::{rng::Rng, TurboRand};
+
+use crate::{
+    animation::DirectionalAnimations,
+    attack::{AttackPhase, ChargePhase, DashCancel, Parrying, RecoverPhase},
+    controller::{Combo, ComboChain},
+    floor::{FloorResource, SpawnFloorEntitiesEvent},
+    game_states::loading::GameAssets,
+    manifest::{player::PlayerManifest, weapon::WeaponManifest},
+    player::{Player, PlayerActions},
+    state::State,
+    GameState, InRun,
+};
+
+pub struct WeaponPlugin;
+
+impl Plugin for WeaponPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                equip_weapon,
+                swap_weapon,
+                pickup_weapon,
+                spawn_weapon_pickups,
+            )
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+//The two weapons the player can carry, by weapon name
+#[derive(Component)]
+pub struct Weapons {
+    pub slots: [Option<String>; 2],
+    pub active: usize,
+}
+
+impl Weapons {
+    pub fn active_weapon(&self) -> Option<&String> {
+        self.slots[self.active].as_ref()
+    }
+}
+
+#[derive(Component)]
+pub struct WeaponPickup(pub String);
+
+#[derive(Bundle)]
+pub struct WeaponPickupBundle {
+    pickup: WeaponPickup,
+    sprite: SpriteBundle,
+}
+
+impl WeaponPickupBundle {
+    pub fn new(weapon: String, location: Vec2, texture: Handle<Image>) -> Self {
+        Self {
+            pickup: WeaponPickup(weapon),
+            sprite: SpriteBundle {
+                //TODO: Add a sprite for each weapon
+                texture,
+                sprite: Sprite {
+                    color: Color::srgb(1., 0.85, 0.3),
+                    ..default()
+                },
+                transform: Transform::from_translation(location.extend(3.)),
+                ..default()
+            },
+        }
+    }
+}
+
+//Spawns the pickup with its name above it
+fn spawn_pickup(commands: &mut Commands, weapon: String, location: Vec2, game_assets: &GameAssets) {
+    let text_style = TextStyle {
+        font: game_assets.font.clone(),
+        font_size: 8.,
+        color: Color::WHITE,
+    };
+
+    commands
+        .spawn((
+            WeaponPickupBundle::new(weapon.clone(), location, game_assets.xp_texture.clone()),
+            StateScoped(InRun),
+        ))
+        .with_children(|parent| {
+            parent.spawn(Text2dBundle {
+                text: Text::from_section(weapon, text_style).with_justify(JustifyText::Center),
+                transform: Transform::from_xyz(0., 12., 1.),
+                ..default()
+            });
+        });
+}
+
+pub(crate) const PICKUP_DISTANCE: f32 = 16.;
+
+//Updates the combo and animations whenever the carried weapons change
+fn equip_weapon(
+    mut query: Query<
+        (
+            &Weapons,
+            &mut ComboChain,
+            &mut DirectionalAnimations,
+            &mut State,
+            Entity,
+        ),
+        Changed<Weapons>,
+    >,
+    weapon_manifest: Res<WeaponManifest>,
+    player_manifest: Res<PlayerManifest>,
+    mut commands: Commands,
+) {
+    for (weapons, mut chain, mut animations, mut state, entity) in query.iter_mut() {
+        let mut new_animations = player_manifest.player_data.animations.clone();
+
+        *chain = match weapons
+            .active_weapon()
+            .and_then(|name| weapon_manifest.get_by_name(name.clone()))
+        {
+            Some(weapon) => {
+                new_animations.0.extend(weapon.animations.0.clone());
+
+                ComboChain {
+                    steps: weapon.combo.clone(),
+                    window: weapon.combo_window,
+                    projectile: weapon.projectile.clone(),
+                }
+            }
+            None => ComboChain::default(),
+        };
+
+        *animations = new_animations;
+
+        //The attack in progress belongs to the old weapon
+        if matches!(*state, State::Attacking(_)) {
+            state.set(State::Idle);
+            commands.entity(entity).remove::<(
+                StateMachine,
+                ChargePhase,
+                AttackPhase,
+                RecoverPhase,
+                DashCancel,
+                Parrying,
+            )>();
+        }
+
+        commands.entity(entity).remove::<Combo>();
+    }
+}
+
+fn swap_weapon(mut query: Query<(&mut Weapons, &State, &ActionState<PlayerActions>)>) {
+    let Ok((mut weapons, state, action_state)) = query.get_single_mut() else {
+        return;
+    };
+
+    if !action_state.just_pressed(&PlayerActions::SwapWeapon)
+        || matches!(*state, State::Attacking(_))
+    {
+        return;
+    }
+
+    let other = 1 - weapons.active;
+
+    if weapons.slots[other].is_some() {
+        weapons.active = other;
+    }
+}
+
+//Takes the weapon into a free slot, or swaps it with the active one
+fn pickup_weapon(
+    mut player_query: Query<(&mut Weapons, &Transform, &ActionState<PlayerActions>), With<Player>>,
+    pickups: Query<(&WeaponPickup, &Transform, Entity), Without<Player>>,
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+) {
+    let Ok((mut weapons, player_transform, action_state)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    if !action_state.just_pressed(&PlayerActions::Interact) {
+        return;
+    }
+
+    let player_pos = player_transform.translation.xy();
+
+    let Some((pickup, transform, entity)) = pickups.iter().find(|(_, transform, _)| {
+        transform.translation.xy().distance(player_pos) < PICKUP_DISTANCE
+    }) else {
+        return;
+    };
+
+    commands.entity(entity).despawn_recursive();
+
+    if let Some(free) = weapons.slots.iter().position(Option::is_none) {
+        weapons.slots[free] = Some(pickup.0.clone());
+        weapons.active = free;
+    } else {
+        let active = weapons.active;
+
+        if let Some(dropped) = weapons.slots[active].replace(pickup.0.clone()) {
+            spawn_pickup(
+                &mut commands,
+                dropped,
+                transform.translation.xy(),
+                &game_assets,
+            );
+        }
+    }
+}
+
+fn spawn_weapon_pickups(
+    mut commands: Commands,
+    mut event: EventReader<SpawnFloorEntitiesEvent>,
+    pickups: Query<Entity, With<WeaponPickup>>,
+    floor: Res<FloorResource>,
+    weapon_manifest: Res<WeaponManifest>,
+    game_assets: Res<GameAssets>,
+) {
+    for e in event.read() {
+        //Weapons left behind are lost when changing floors
+        for entity in pickups.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+
+        let Some(chance) = floor.domain.as_ref().and_then(|d| d.weapons) else {
+            continue;
+        };
+
+        let names = weapon_manifest
+            .weapons
+            .values()
+            .map(|weapon| weapon.name.clone())
+            .collect::<Vec<_>>();
+
+        if names.is_empty() {
+            continue;
+        }
+
+        let rand = Rng::new();
+
+        for pos in e.room_centers.iter() {
+            if rand.f32() > chance {
+                continue;
+            }
+
+            let weapon = names[rand.usize(0..names.len())].clone();
+
+            spawn_pickup(&mut commands, weapon, *pos, &game_assets);
+        }
+    }
+}